@@ -44,6 +44,7 @@ pub fn App() -> impl IntoView {
                     <Route path="/about" view=|| view! {  <RouteAbout/> }/>
                     <Route path="/color-picker" view=|| view! {  <RouteColorPicker/> }/>
                     <Route path="/color-picker/test" view=|| view! {  <RouteComingSoon/> }/>
+                    <Route path="/gradient" view=|| view! {  <RouteGradient/> }/>
                     <Route path="/base-converter" view=|| view! {  <RouteComingSoon/> }/>
                     <Route path="/time-zones" view=|| view! {  <RouteComingSoon/> }/>
                 </Routes>
@@ -91,6 +92,7 @@ pub fn SideNav() -> impl IntoView {
     let paths = vec![
         ("/", "Home"),
         ("/color-picker", "Color Picker"),
+        ("/gradient", "Gradient Builder"),
         ("/base-converter", "Base Converter"),
         ("/time-zones", "Time Zone Converter"),
     ];
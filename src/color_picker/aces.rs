@@ -0,0 +1,154 @@
+use super::*;
+
+/// Source: https://docs.acescentral.com/specifications/acescg/
+pub const AP0_TO_XYZ: Matrix3 = [
+    [0.9525523959, 0.0000000000, 0.0000936786],
+    [0.3439664498, 0.7281660966, -0.0721325464],
+    [0.0000000000, 0.0000000000, 1.0088251844],
+];
+/// Source: https://docs.acescentral.com/specifications/acescg/
+pub const XYZ_TO_AP0: Matrix3 = [
+    [1.0498110175, 0.0000000000, -0.0000974845],
+    [-0.4959030231, 1.3733130458, 0.0982400361],
+    [0.0000000000, 0.0000000000, 0.9912520182],
+];
+/// Source: https://docs.acescentral.com/specifications/acescg/
+pub const AP1_TO_XYZ: Matrix3 = [
+    [0.6624541811, 0.1340042065, 0.1561876870],
+    [0.2722287168, 0.6740817658, 0.0536895174],
+    [-0.0055746495, 0.0040607335, 1.0103391003],
+];
+/// Source: https://docs.acescentral.com/specifications/acescg/
+pub const XYZ_TO_AP1: Matrix3 = [
+    [1.6410233797, -0.3248032942, -0.2364246952],
+    [-0.6636628587, 1.6153315917, 0.0167563477],
+    [0.0117218943, -0.0082844420, 0.9883948585],
+];
+
+/// Convert sRGB to the linear primaries described by `xyz_to_primaries`,
+/// adapting from the D65 white of sRGB to the D60 white of ACES.
+fn aces_from_rgb(rgb: Rgb, xyz_to_primaries: &Matrix3) -> (f64, f64, f64) {
+    let xyz_d65 = Xyz::from_rgb(rgb).as_tuple();
+    let xyz_d60 = multiply_matrix_vector(
+        &chromatic_adaptation_matrix(WHITE_D65, WHITE_D60),
+        xyz_d65,
+    );
+
+    multiply_matrix_vector(xyz_to_primaries, xyz_d60)
+}
+
+fn aces_as_rgb(values: (f64, f64, f64), primaries_to_xyz: &Matrix3) -> Rgb {
+    let xyz_d60 = multiply_matrix_vector(primaries_to_xyz, values);
+    let xyz_d65 = multiply_matrix_vector(
+        &chromatic_adaptation_matrix(WHITE_D60, WHITE_D65),
+        xyz_d60,
+    );
+
+    Xyz::from_tuple(xyz_d65).as_rgb()
+}
+
+/// A scene linear color using the ACES AP1 primaries, which is the working
+/// space most renderers and compositors expect.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+/// use approx::assert_relative_eq;
+///
+/// let white = AcesCg::from_rgb(Rgb::from_components((255., 255., 255.)));
+///
+/// assert_relative_eq!(white.r, 1., epsilon = 1e-3);
+/// assert_relative_eq!(white.g, 1., epsilon = 1e-3);
+/// assert_relative_eq!(white.b, 1., epsilon = 1e-3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AcesCg {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+}
+
+impl Color for AcesCg {
+    const COMPONENT_MAXES: (f64, f64, f64) = (1., 1., 1.);
+    const COLOR_SPACE: ColorSpace = ColorSpace::AcesCg;
+
+    fn as_components(&self) -> (f64, f64, f64) {
+        (self.r, self.g, self.b)
+    }
+    fn from_components(components: (f64, f64, f64)) -> Self {
+        let components = Self::clamp_components(components);
+
+        Self {
+            r: components.0,
+            g: components.1,
+            b: components.2,
+        }
+    }
+
+    fn as_floats(&self) -> (f64, f64, f64) {
+        Self::components_to_floats(self.as_components())
+    }
+    fn from_floats(floats: (f64, f64, f64)) -> Self {
+        Self::from_components(Self::floats_to_components(floats))
+    }
+
+    fn as_rgb(&self) -> Rgb {
+        aces_as_rgb(self.as_components(), &AP1_TO_XYZ)
+    }
+    fn from_rgb(rgb: Rgb) -> Self {
+        Self::from_components(aces_from_rgb(rgb, &XYZ_TO_AP1))
+    }
+}
+
+/// A scene linear color using the ACES AP0 primaries, which is the
+/// interchange and archival encoding of ACES.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+/// use approx::assert_relative_eq;
+///
+/// let red = Aces2065::from_rgb(Rgb::from_components((255., 0., 0.)));
+///
+/// assert_relative_eq!(red.r, 0.4397, epsilon = 1e-3);
+/// assert_relative_eq!(red.g, 0.0898, epsilon = 1e-3);
+/// assert_relative_eq!(red.b, 0.0175, epsilon = 1e-3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aces2065 {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+}
+
+impl Color for Aces2065 {
+    const COMPONENT_MAXES: (f64, f64, f64) = (1., 1., 1.);
+    const COLOR_SPACE: ColorSpace = ColorSpace::Aces2065;
+
+    fn as_components(&self) -> (f64, f64, f64) {
+        (self.r, self.g, self.b)
+    }
+    fn from_components(components: (f64, f64, f64)) -> Self {
+        let components = Self::clamp_components(components);
+
+        Self {
+            r: components.0,
+            g: components.1,
+            b: components.2,
+        }
+    }
+
+    fn as_floats(&self) -> (f64, f64, f64) {
+        Self::components_to_floats(self.as_components())
+    }
+    fn from_floats(floats: (f64, f64, f64)) -> Self {
+        Self::from_components(Self::floats_to_components(floats))
+    }
+
+    fn as_rgb(&self) -> Rgb {
+        aces_as_rgb(self.as_components(), &AP0_TO_XYZ)
+    }
+    fn from_rgb(rgb: Rgb) -> Self {
+        Self::from_components(aces_from_rgb(rgb, &XYZ_TO_AP0))
+    }
+}
@@ -0,0 +1,104 @@
+//! Relative adjustments that can be applied to many colors at once, like all
+//! of the colors of a palette.
+
+use super::*;
+
+/// Relative adjustments of colors, measured in a color space with a hue. They
+/// are applied in the order of the fields.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Adjustments {
+    /// Rotate the hue by 180° and invert the lightness. In HSL this is the
+    /// same as inverting the RGB channels.
+    pub invert: bool,
+    /// How many degrees to rotate the hue by.
+    pub hue_rotation: f64,
+    /// What to multiply the chroma or saturation by.
+    pub chroma_scale: f64,
+    /// How much to add to the lightness, in the range -1 to 1.
+    pub lightness_shift: f64,
+}
+
+impl Default for Adjustments {
+    fn default() -> Self {
+        Self {
+            invert: false,
+            hue_rotation: 0.,
+            chroma_scale: 1.,
+            lightness_shift: 0.,
+        }
+    }
+}
+
+impl Adjustments {
+    /// Whether applying the adjustments leaves colors unchanged.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Adjust a color in `space`. The color keeps its current color space.
+    ///
+    /// Returns `None` if `space` doesn't have a hue.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// let orange = DynamicColor::new((255., 136., 0.), ColorSpace::Rgb);
+    /// let adjust = |adjustments: Adjustments, space| {
+    ///     adjustments
+    ///         .apply(orange, space)
+    ///         .map(|color| color.to_color::<Rgb>().as_hex_code())
+    /// };
+    ///
+    /// let inverted = Adjustments {
+    ///     invert: true,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(adjust(inverted, ColorSpace::Hsl), Some("0077ff".to_owned()));
+    ///
+    /// let rotated = Adjustments {
+    ///     hue_rotation: 120.,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(adjust(rotated, ColorSpace::Hsv), Some("00ff88".to_owned()));
+    ///
+    /// let muted = Adjustments {
+    ///     chroma_scale: 0.,
+    ///     lightness_shift: -0.1,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(adjust(muted, ColorSpace::Hsl), Some("666666".to_owned()));
+    ///
+    /// assert_eq!(adjust(Adjustments::default(), ColorSpace::Rgb), None);
+    /// ```
+    pub fn apply(
+        &self,
+        color: DynamicColor,
+        space: ColorSpace,
+    ) -> Option<DynamicColor> {
+        let chroma = space.chroma_component()?;
+        let lightness = space.lightness_component()?;
+        let color_space = color.color_space();
+
+        let mut adjusted = color.set_color_space(space);
+        let scale = |adjusted: DynamicColor, index: usize, scale: f64| {
+            let float = (adjusted.float(index) * scale).clamp(0., 1.);
+            adjusted.set_float(index, float)
+        };
+        let shift = |adjusted: DynamicColor, index: usize, shift: f64| {
+            let float = (adjusted.float(index) + shift).clamp(0., 1.);
+            adjusted.set_float(index, float)
+        };
+
+        if self.invert {
+            adjusted = adjusted.rotate_hue(180., space)?;
+            adjusted =
+                adjusted.set_float(lightness, 1. - adjusted.float(lightness));
+        }
+        adjusted = adjusted.rotate_hue(self.hue_rotation, space)?;
+        adjusted = scale(adjusted, chroma, self.chroma_scale);
+        adjusted = shift(adjusted, lightness, self.lightness_shift);
+
+        Some(adjusted.set_color_space(color_space))
+    }
+}
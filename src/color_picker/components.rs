@@ -1,22 +1,40 @@
-use std::marker::PhantomData;
+use std::{collections::HashSet, marker::PhantomData};
 
 use crate::{
     color_picker::*,
     components::*,
-    settings::{StoredInput, StoredRadioGroup},
-    string_utils::*,
+    settings::{
+        local_storage_set_value, local_storage_value, StoredInput,
+        StoredRadioGroup,
+    },
+    toasts,
     utils::*,
 };
-use leptos::{
-    ev::*,
-    html::*,
-    logging::{error, log},
-    *,
-};
+use leptos::{ev::*, html::*, logging::error, *};
 use wasm_bindgen::prelude::*;
 
+/// How many committed colors the picker remembers for undoing.
+const HISTORY_CAPACITY: usize = 100;
+/// How many colors are shown in the recent colors strip.
+const RECENT_COLORS_COUNT: usize = 12;
+
 #[component]
-pub fn ColorPicker() -> impl IntoView {
+pub fn ColorPicker(
+    /// The edited color. Changes made to it from outside of the picker are
+    /// picked up as well.
+    #[prop(optional)]
+    color: Option<RwSignal<DynamicColor>>,
+    /// Whether the inputs show extra decimals.
+    #[prop(optional)]
+    precise_inputs: Option<RwSignal<bool>>,
+    /// Whether the inputs show floats in the range 0 to 1.
+    #[prop(optional)]
+    normalised_inputs: Option<RwSignal<bool>>,
+    /// Settings which take precedence over the stored ones when the picker is
+    /// loaded, like the ones from a shared link.
+    #[prop(optional)]
+    link: Option<ColorLink>,
+) -> impl IntoView {
     const DECIMAL_PRECISION: usize = 2;
 
     // let (color_space_options_old, _) = create_signal(
@@ -27,17 +45,29 @@ pub fn ColorPicker() -> impl IntoView {
         ("RGB".to_owned(), ColorSpace::Rgb),
         ("HSL".to_owned(), ColorSpace::Hsl),
         ("HSV".to_owned(), ColorSpace::Hsv),
+        ("ACEScg".to_owned(), ColorSpace::AcesCg),
+        ("ACES2065-1".to_owned(), ColorSpace::Aces2065),
+        ("OKLCh".to_owned(), ColorSpace::Oklch),
     ];
 
-    let (color_space, set_color_space) = create_signal(ColorSpace::Rgb);
+    let link = link.unwrap_or_default();
 
-    let precise_inputs = create_rw_signal(false);
-    let normalised_inputs = create_rw_signal(false);
+    let (color_space, set_color_space) =
+        create_signal(link.color_space.unwrap_or(ColorSpace::Rgb));
 
-    let (color, set_color) = create_signal(DynamicColor::from_floats(
-        (1., 1., 1.),
-        color_space.get_untracked(),
-    ));
+    let precise_inputs =
+        precise_inputs.unwrap_or_else(|| create_rw_signal(false));
+    let normalised_inputs =
+        normalised_inputs.unwrap_or_else(|| create_rw_signal(false));
+
+    let (color, set_color) = color
+        .unwrap_or_else(|| {
+            create_rw_signal(DynamicColor::from_floats(
+                (1., 1., 1.),
+                color_space.get_untracked(),
+            ))
+        })
+        .split();
 
     let (color_hsv, set_color_hsv) =
         create_signal(color.get_untracked().to_color::<Hsv>());
@@ -46,12 +76,54 @@ pub fn ColorPicker() -> impl IntoView {
         create_signal(color.get_untracked().to_color::<Rgb>().as_hex_code());
     let hex_code_hashtag = Signal::derive(move || format!("#{}", hex_code()));
 
+    // The color that edits are compared against. It follows the color set from
+    // outside of the picker and the start of every new edit, unless the user
+    // pinned one.
+    let (original, set_original) =
+        create_signal(color.get_untracked().to_color::<Rgb>());
+    let (original_pinned, set_original_pinned) = create_signal(false);
+    // The last color set by the picker itself, to tell changes made from
+    // outside of it apart.
+    let picker_color = store_value(color.get_untracked().to_color::<Rgb>());
+
     let set_color_sync_other = move |color: DynamicColor| {
+        picker_color.set_value(color.to_color());
         set_color(color);
         set_color_hsv(color.to_color::<Hsv>());
         set_hex_code(color.to_color::<Rgb>().as_hex_code());
     };
 
+    let same_rgb = |a: Rgb, b: Rgb| {
+        [(a.r, b.r), (a.g, b.g), (a.b, b.b)]
+            .into_iter()
+            .all(|(a, b)| a.float_compare_digits(b, -2))
+    };
+
+    // The HSV color is kept separately to not lose the hue of grays, so it's
+    // only replaced when the color was changed from outside of the picker.
+    create_effect(move |_| {
+        let rgb = color().to_color::<Rgb>();
+        if same_rgb(rgb, color_hsv.get_untracked().as_rgb()) {
+            return;
+        }
+
+        set_color_hsv(rgb.as_color::<Hsv>());
+        set_hex_code(rgb.as_hex_code());
+    });
+
+    // The OKLCh plane keeps its color for the same reason, which also keeps
+    // the chroma of colors outside of the sRGB gamut.
+    let (color_oklch, set_color_oklch) =
+        create_signal(color.get_untracked().to_color::<Oklch>());
+    create_effect(move |_| {
+        let rgb = color().to_color::<Rgb>();
+        if same_rgb(rgb, color_oklch.get_untracked().as_rgb()) {
+            return;
+        }
+
+        set_color_oklch(rgb.as_color::<Oklch>());
+    });
+
     // let hex_code = create_memo( move |_| {
     //     let rgb = color().to_color::<Rgb>();
     //     rgb.as_hex_code()
@@ -69,182 +141,80 @@ pub fn ColorPicker() -> impl IntoView {
 
     let on_color_space_change = set_color_space;
 
-    let (force_update_inputs, set_force_update_inputs) = create_signal(false);
-
-    let component_0_ref = create_node_ref::<Input>();
-    let component_1_ref = create_node_ref::<Input>();
-    let component_2_ref = create_node_ref::<Input>();
-    let float_component_0_ref = create_node_ref::<Input>();
-    let float_component_1_ref = create_node_ref::<Input>();
-    let float_component_2_ref = create_node_ref::<Input>();
+    let component_decimals = Signal::derive(move || {
+        let decimals = color().color_space().info().component_decimals;
+        match precise_inputs.get() {
+            true => decimals + 2,
+            false => decimals,
+        }
+    });
 
-    let format_component = move || match precise_inputs.get() {
-        true => |value: f64| -> _ { naturally_format_float(value, 0, 2) },
-        false => |value: f64| -> _ { naturally_format_float(value, 0, 0) },
+    let format_component = move || {
+        let decimals = component_decimals();
+        move |value: f64| -> _ { naturally_format_float(value, 0, decimals) }
     };
 
     let format_float =
         |value: f64| -> _ { naturally_format_float(value, 1, 2) };
 
-    create_effect(move |_| {
-        precise_inputs.track();
-        set_force_update_inputs.set_untracked(true);
-    });
-
-    create_effect(move |_| {
-        let components = color().components();
-        let floats = color().as_floats();
-
-        let Some(component_0) = component_0_ref.get() else {
-            // error!("couldn't find component_1");
-            return;
-        };
-        let Some(component_1) = component_1_ref.get() else {
-            // error!("couldn't find component_2");
-            return;
-        };
-        let Some(component_2) = component_2_ref.get() else {
-            // error!("couldn't find component_3");
-            return;
-        };
-
-        let Some(float_0) = float_component_0_ref.get() else {
-            // error!("couldn't find float_component_1");
-            return;
-        };
-        let Some(float_1) = float_component_1_ref.get() else {
-            // error!("couldn't find float_component_2");
-            return;
-        };
-        let Some(float_2) = float_component_2_ref.get() else {
-            // error!("couldn't find float_component_3");
-            return;
-        };
-
-        // log!("set components {:?}, floats {:?}", components, floats);
-
-        let force_update = force_update_inputs.get_untracked();
-        if force_update {
-            set_force_update_inputs.set_untracked(false);
-        }
-
-        sync_input_value_float(
-            &component_0,
-            components.0,
-            DECIMAL_PRECISION,
-            force_update,
-            format_component(),
-        );
-        sync_input_value_float(
-            &component_1,
-            components.1,
-            DECIMAL_PRECISION,
-            force_update,
-            format_component(),
-        );
-        sync_input_value_float(
-            &component_2,
-            components.2,
-            DECIMAL_PRECISION,
-            force_update,
-            format_component(),
-        );
-
-        sync_input_value_float(
-            &float_0,
-            floats.0,
-            DECIMAL_PRECISION,
-            force_update,
-            format_float,
-        );
-        sync_input_value_float(
-            &float_1,
-            floats.1,
-            DECIMAL_PRECISION,
-            force_update,
-            format_float,
-        );
-        sync_input_value_float(
-            &float_2,
-            floats.2,
-            DECIMAL_PRECISION,
-            force_update,
-            format_float,
-        );
-    });
-
-    // create_tri
-
-    let update_with_components = move |ev: Event| {
-        let Some(component_0) = component_0_ref.get() else {
-            error!("couldn't find component_1");
-            return;
-        };
-        let Some(component_1) = component_1_ref.get() else {
-            error!("couldn't find component_2");
-            return;
-        };
-        let Some(component_2) = component_2_ref.get() else {
-            error!("couldn't find component_3");
-            return;
-        };
-
-        let components = (
-            component_0.value().parse_input::<f64>().unwrap_or(0.),
-            component_1.value().parse_input::<f64>().unwrap_or(0.),
-            component_2.value().parse_input::<f64>().unwrap_or(0.),
-        );
-
-        let format_component = format_component();
-
-        if &ev.type_() == "change" {
-            component_0.set_value(&format_component(components.0));
-            component_1.set_value(&format_component(components.1));
-            component_2.set_value(&format_component(components.2));
+    let component_maxes =
+        create_memo(move |_| color_space().color_component_maxes());
+    // The hue is the only angle, which can also be typed in other units.
+    let component_full_turn = move |index: usize| {
+        Signal::derive(move || {
+            (color_space().hue_component() == Some(index)).then_some(360.)
+        })
+    };
+    let float_full_turn = move |index: usize| {
+        Signal::derive(move || {
+            (color_space().hue_component() == Some(index)).then_some(1.)
+        })
+    };
+    // Color spaces with small maxes are stepped by hundredths, like floats.
+    let component_step = move |max: f64| if max > 10. { 1. } else { 0.01 };
+
+    let component = move |index: usize| {
+        Signal::derive(move || {
+            let (a, b, c) = color().components();
+            [a, b, c][index]
+        })
+    };
+    let float =
+        move |index: usize| Signal::derive(move || color().float(index));
+    let set_component = move |index: usize| {
+        move |value: f64| {
+            let color = color.get_untracked();
+            set_color_sync_other(match index {
+                0 => color.set_component_0(value),
+                1 => color.set_component_1(value),
+                _ => color.set_component_2(value),
+            });
         }
-
-        // log!("got components {:?}", components);
-
-        set_color_sync_other(color.get_untracked().set_components(components));
     };
-    let update_with_floats = move |ev: Event| {
-        let Some(float_0) = float_component_0_ref.get() else {
-            error!("couldn't find float_component_1");
-            return;
-        };
-        let Some(float_1) = float_component_1_ref.get() else {
-            error!("couldn't find float_component_2");
-            return;
-        };
-        let Some(float_2) = float_component_2_ref.get() else {
-            error!("couldn't find float_component_3");
-            return;
-        };
-
-        let floats = (
-            float_0.value().parse_input::<f64>().unwrap_or(0.),
-            float_1.value().parse_input::<f64>().unwrap_or(0.),
-            float_2.value().parse_input::<f64>().unwrap_or(0.),
-        );
-
-        if &ev.type_() == "change" {
-            float_0.set_value(&format_float(floats.0));
-            float_1.set_value(&format_float(floats.1));
-            float_2.set_value(&format_float(floats.2));
+    let set_float = move |index: usize| {
+        move |value: f64| {
+            set_color_sync_other(color.get_untracked().set_float(index, value));
         }
-
-        // log!("got floats {:?}", floats);
-
-        set_color_sync_other(color().set_floats(floats));
     };
 
     let hue_float = Signal::derive(move || color_hsv().as_floats().0);
     let sat_float = Signal::derive(move || color_hsv().as_floats().1);
     let value_float = Signal::derive(move || color_hsv().as_floats().2);
 
+    // Drags on the picker keep the locked property at the value it had in
+    // the last committed color.
+    let (lock_target, set_lock_target) =
+        create_signal::<Option<LockTarget>>(None);
+    let (lock, set_lock) = create_signal::<Option<ColorLock>>(None);
+
     let update_with_hsv_floats = move |floats: (f64, f64, f64)| {
-        set_color_hsv(Hsv::from_floats((floats.0, floats.1, floats.2)));
+        let mut hsv = Hsv::from_floats((floats.0, floats.1, floats.2));
+        if let Some(lock) = lock.get_untracked() {
+            hsv = lock.apply(hsv);
+        }
+        set_color_hsv(hsv);
         let hsv = DynamicColor::from_color(color_hsv.get_untracked());
+        picker_color.set_value(hsv.to_color());
         set_color(hsv.set_color_space(color.get_untracked().color_space()));
         set_hex_code(hsv.to_color::<Rgb>().as_hex_code());
     };
@@ -272,6 +242,188 @@ pub fn ColorPicker() -> impl IntoView {
         ));
     };
 
+    // Like `set_color_sync_other`, but moving the color within the lock.
+    let set_color_locked = move |color: DynamicColor| {
+        if lock.with_untracked(Option::is_some) {
+            update_with_hsv_floats(color.to_color::<Hsv>().as_floats());
+        } else {
+            set_color_sync_other(color);
+        }
+    };
+
+    let update_with_oklch = move |oklch: Oklch| {
+        if lock.with_untracked(Option::is_some) {
+            set_color_locked(DynamicColor::from_color(oklch));
+            return;
+        }
+        set_color_oklch(oklch);
+        set_color_sync_other(
+            DynamicColor::from_color(oklch)
+                .set_color_space(color.get_untracked().color_space()),
+        );
+    };
+
+    // Colors are only committed to the history once the user finishes an
+    // interaction, so that a drag or a typed value counts as a single step.
+    // The colors are stored as they were, since converting them between color
+    // spaces doesn't round-trip exactly. For the same reason, colors are
+    // compared with a tolerance to tell whether they changed.
+    let history =
+        create_rw_signal(History::<DynamicColor>::new(HISTORY_CAPACITY));
+    // Whether the next change starts a new edit.
+    let edit_committed = store_value(false);
+    let commit_color = move || {
+        let current = color.get_untracked();
+        let changed = history.with_untracked(|history| {
+            history.present().is_none_or(|present| {
+                !same_rgb(present.to_color(), current.to_color())
+            })
+        });
+        if changed {
+            history.update(|history| {
+                history.commit(current);
+            });
+            edit_committed.set_value(true);
+        }
+    };
+    commit_color();
+    create_managed_window_event_listener("pointerup", move |_| commit_color());
+    create_managed_window_event_listener("click", move |_| commit_color());
+    create_managed_window_event_listener("change", move |_| commit_color());
+
+    create_effect(move |_| {
+        let rgb = color().to_color::<Rgb>();
+        let from_outside = !same_rgb(rgb, picker_color.get_value());
+        picker_color.set_value(rgb);
+        if original_pinned.get_untracked() {
+            return;
+        }
+
+        if from_outside {
+            edit_committed.set_value(false);
+            set_original(rgb);
+        } else if edit_committed.get_value() {
+            edit_committed.set_value(false);
+            if let Some(present) = history.with_untracked(|history| {
+                history.present().map(|present| present.to_color::<Rgb>())
+            }) {
+                set_original(present);
+            }
+        }
+    });
+
+    let (geometry, set_geometry) = create_signal(PickerGeometry::Square);
+    let geometry_options = PickerGeometry::ALL
+        .into_iter()
+        .map(|geometry| (geometry.to_ui_string(()), geometry))
+        .collect::<Vec<_>>();
+
+    let geometry_view = move || {
+        let geometry = geometry();
+        match geometry {
+            PickerGeometry::Square => view! {
+                <SatValueSurface
+                    sat=sat_float
+                    set_sat=on_sat_float_change
+                    value=value_float
+                    set_value=on_value_float_change
+                    hue=hue_float
+                    on_commit=commit_color
+                />
+                <HueSlider
+                    hue=hue_float
+                    set_hue=on_hue_float_change
+                    on_commit=commit_color
+                />
+            }
+            .into_view(),
+            PickerGeometry::Wheel | PickerGeometry::Triangle => view! {
+                <GeometrySurface
+                    geometry=geometry
+                    hue=hue_float
+                    set_hue=on_hue_float_change
+                    cursor=Signal::derive(move || {
+                        geometry.sat_value_position(
+                            hue_float(),
+                            sat_float(),
+                            value_float(),
+                        )
+                    })
+                    set_cursor=move |x, y| {
+                        let hue = hue_float.get_untracked();
+                        let (sat, value) = geometry.sat_value_at(hue, x, y);
+                        update_with_hsv_floats((hue, sat, value));
+                    }
+                    value_text=Signal::derive(move || {
+                        format!(
+                            "Saturation {:.0}%, Value {:.0}%",
+                            sat_float() * 100.,
+                            value_float() * 100.,
+                        )
+                    })
+                    on_commit=commit_color
+                />
+            }
+            .into_view(),
+            PickerGeometry::OklchPlane => {
+                // The hues of the slider have a medium lightness and chroma,
+                // which are inside of the sRGB gamut.
+                let hue_gradient =
+                    DynamicColor::new((70., 30., 0.), ColorSpace::Oklch)
+                        .component_gradient(2, 24)
+                        .into_iter()
+                        .map(|rgb| format!("#{}", rgb.as_hex_code()))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                let oklch_hue = Signal::derive(move || color_oklch().h / 360.);
+                let set_oklch_hue = move |hue: f64| {
+                    update_with_oklch(Oklch {
+                        h: hue * 360.,
+                        ..color_oklch.get_untracked()
+                    })
+                };
+
+                view! {
+                    <GeometrySurface
+                        geometry=geometry
+                        hue=oklch_hue
+                        set_hue=set_oklch_hue
+                        cursor=Signal::derive(move || {
+                            let oklch = color_oklch();
+                            oklch_plane_position(oklch.l, oklch.c)
+                        })
+                        set_cursor=move |x, y| {
+                            let (l, c) = oklch_plane_at(x, y);
+                            update_with_oklch(Oklch {
+                                l,
+                                c,
+                                ..color_oklch.get_untracked()
+                            });
+                        }
+                        value_text=Signal::derive(move || {
+                            let oklch = color_oklch();
+                            format!(
+                                "Lightness {:.0}%, Chroma {:.0}%",
+                                oklch.l,
+                                oklch.c,
+                            )
+                        })
+                        on_commit=commit_color
+                    />
+                    <HueSlider
+                        hue=oklch_hue
+                        set_hue=set_oklch_hue
+                        gradient=Signal::derive(move || {
+                            format!("linear-gradient(to right, {})", hue_gradient)
+                        })
+                        on_commit=commit_color
+                    />
+                }
+                .into_view()
+            }
+        }
+    };
+
     let update_with_hex_code = move |hex: &str| {
         let Some(rgb) = Rgb::from_hex_code(hex) else {
             return;
@@ -282,11 +434,18 @@ pub fn ColorPicker() -> impl IntoView {
             color.get_untracked().color_space(),
         );
 
+        picker_color.set_value(rgb);
         set_color(color);
         set_color_hsv(color.to_color::<Hsv>());
         set_hex_code(hex.to_owned());
     };
 
+    // Hex codes are only marked invalid once they can't be completed anymore.
+    let hex_code_invalid = create_rw_signal(false);
+    let hex_code_error_id = format!("hex-code-error-{}", unique_id());
+    const HEX_CODE_FORMAT: &str =
+        "Expected a hex code with six digits from 0 to f, like #ff8800";
+
     let on_hex_code_change = move |ev: Event| {
         let value = event_target_value(&ev);
         let mut value_str = value.as_str();
@@ -300,8 +459,150 @@ pub fn ColorPicker() -> impl IntoView {
             // set_hex_code(value_str.to_owned());
         }
 
+        hex_code_invalid.set(
+            value_str.len() > 6
+                || !value_str.chars().all(|c| c.is_ascii_hexdigit()),
+        );
         update_with_hex_code(value_str);
     };
+    // Incomplete or invalid hex codes are replaced by the last valid color.
+    let on_hex_code_commit = move |ev: Event| {
+        let value = event_target_value(&ev);
+        hex_code_invalid.set(false);
+        if Rgb::from_hex_code(value.trim()).is_some() {
+            return;
+        }
+
+        if !value.trim().is_empty() {
+            toasts::add_toast(format!(
+                "Couldn't read '{}'. {}",
+                value.trim(),
+                HEX_CODE_FORMAT
+            ));
+        }
+        event_target::<web_sys::HtmlInputElement>(&ev)
+            .set_value(&hex_code.get_untracked());
+    };
+
+    create_effect(move |_| {
+        history.track();
+        set_lock(
+            lock_target()
+                .map(|target| ColorLock::new(target, color.get_untracked())),
+        );
+    });
+    // Components are locked in the color space they were locked in.
+    create_effect(move |_| {
+        color_space.track();
+        if let Some(LockTarget::Component(_)) = lock_target.get_untracked() {
+            set_lock_target(None);
+        }
+    });
+    let toggle_lock = move |target: LockTarget| {
+        set_lock_target(match lock_target.get_untracked() {
+            Some(locked) if locked == target => None,
+            _ => Some(target),
+        });
+    };
+    let lock_button = move |target: LockTarget, label: Signal<String>| {
+        let locked = move || lock_target() == Some(target);
+        view! {
+            <button
+                class="lock"
+                aria-pressed=move || locked().to_string()
+                title=move || format!("Lock {}", label())
+                on:click=move |_| toggle_lock(target)
+            >
+                {move || {
+                    if locked() {
+                        view! { <Icon icon_id="lock"/> }
+                    } else {
+                        view! { <Icon icon_id="unlock"/> }
+                    }
+                }}
+                <span class="label">{label}</span>
+            </button>
+        }
+    };
+
+    let restore_color = move |rgb: Rgb| {
+        set_color_sync_other(
+            DynamicColor::from_color(rgb)
+                .set_color_space(color.get_untracked().color_space()),
+        );
+    };
+    // Colors from the history are restored as they were stored, so that they
+    // aren't committed again as slightly different colors.
+    let undo = move || {
+        commit_color();
+        if let Some(color) =
+            history.try_update(|history| history.undo()).flatten()
+        {
+            set_color_sync_other(color);
+        }
+    };
+    let redo = move || {
+        if let Some(color) =
+            history.try_update(|history| history.redo()).flatten()
+        {
+            set_color_sync_other(color);
+        }
+    };
+
+    // The shortcuts only apply to this picker, while the focus is inside it.
+    let on_history_key_down = move |ev: KeyboardEvent| {
+        if !(ev.ctrl_key() || ev.meta_key()) || ev.alt_key() {
+            return;
+        }
+        // Text fields have their own undo history.
+        if is_text_field_event(&ev) {
+            return;
+        }
+
+        match ev.key().to_lowercase().as_str() {
+            "z" if ev.shift_key() => redo(),
+            "z" => undo(),
+            "y" => redo(),
+            _ => return,
+        }
+        ev.prevent_default();
+    };
+
+    // The most recent distinct colors, not including the current one.
+    let recent_colors = move || {
+        history.with(|history| {
+            let mut hex_codes = history
+                .present()
+                .map(|color| color.to_color::<Rgb>().as_hex_code())
+                .into_iter()
+                .collect::<Vec<_>>();
+            history
+                .recent()
+                .map(|color| color.to_color::<Rgb>())
+                .filter(|rgb| {
+                    let hex_code = rgb.as_hex_code();
+                    let new = !hex_codes.contains(&hex_code);
+                    hex_codes.push(hex_code);
+                    new
+                })
+                .take(RECENT_COLORS_COUNT)
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let recent_color_view = move |rgb: Rgb| {
+        let hex_code = format!("#{}", rgb.as_hex_code());
+        view! {
+            <li>
+                <button
+                    style=rgb.as_css_variables()
+                    title=format!("Restore {}", hex_code)
+                    aria-label=format!("Restore {}", hex_code)
+                    on:click=move |_| restore_color(rgb)
+                />
+            </li>
+        }
+    };
 
     let color_space_info = create_memo(move |_| color_space().info());
 
@@ -351,13 +652,35 @@ pub fn ColorPicker() -> impl IntoView {
     //     set_precise_inputs(checked);
     // };
 
-    let color_display_style = move || {
-        let rgb: Rgb = color().to_color();
+    let color_display_style =
+        move || color().to_color::<Rgb>().as_css_variables();
 
-        format!(
-            "--r: {}; --g: {}; --b: {};",
-            rgb.r as u8, rgb.g as u8, rgb.b as u8
-        )
+    let original_style = move || original().as_css_variables();
+    let is_original = move || same_rgb(original(), color().to_color::<Rgb>());
+    let delta_e = move || {
+        let current = Lab::from_rgb(color().to_color());
+        format!("ΔE {:.1}", Lab::from_rgb(original()).delta_e(&current))
+    };
+    let contrast = move || {
+        let ratio = contrast_ratio(original(), color().to_color());
+        format!("{:.2}:1", ratio)
+    };
+    // The window click listener commits the reverted color to the history.
+    // Reverting isn't a new edit, so the original is kept.
+    let revert = move |_| {
+        edit_committed.set_value(false);
+        restore_color(original.get_untracked());
+    };
+    let toggle_pin = move |_| {
+        set_original(color.get_untracked().to_color());
+        set_original_pinned(!original_pinned.get_untracked());
+    };
+    let pin_label = move || {
+        if original_pinned() {
+            "Unpin the original"
+        } else {
+            "Pin the current color as the original"
+        }
     };
 
     // let id = unique_id();
@@ -371,128 +694,164 @@ pub fn ColorPicker() -> impl IntoView {
     view! {
         <div
             class="color-picker"
+            on:keydown=on_history_key_down
             class:normalised=normalised_inputs
         >
-            <div class="map">
-                <SatValueSurface
-                    sat=sat_float
-                    set_sat=on_sat_float_change
-                    value=value_float
-                    set_value=on_value_float_change
-                    hue=hue_float
-                />
-                <HueSlider
-                    hue=hue_float
-                    set_hue=on_hue_float_change
+            <div class="map">{geometry_view}</div>
+            <div class="component-sliders">
+                <ComponentSliders
+                    color=color
+                    set_color=set_color_locked
+                    on_commit=commit_color
                 />
             </div>
             <div class="controls">
                 <div class="integers">
-                    <LabeledFloatInput
+                    <NumberInput
+                        value=component(0)
+                        set_value=set_component(0)
+                        min=0.
+                        max=Signal::derive(move || component_maxes().0)
+                        step=Signal::derive(move || {
+                            component_step(component_maxes().0)
+                        })
+                        full_turn=component_full_turn(0)
+                        decimals=component_decimals
                         prefix=label_0
                         postfix=unit_0
-                    >
-                        <input
-                            type="text"
-                            inputmode="decimal"
-                            placeholder="100"
-                            value="255"
-                            size=6
-                            on:input=update_with_components
-                            on:change=update_with_components
-                            _ref=component_0_ref
-                        />
-                    </LabeledFloatInput>
-                    <LabeledFloatInput
+                        placeholder="100"
+                    />
+                    <NumberInput
+                        value=component(1)
+                        set_value=set_component(1)
+                        min=0.
+                        max=Signal::derive(move || component_maxes().1)
+                        step=Signal::derive(move || {
+                            component_step(component_maxes().1)
+                        })
+                        full_turn=component_full_turn(1)
+                        decimals=component_decimals
                         prefix=label_1
                         postfix=unit_1
-                    >
-                        <input
-                            type="text"
-                            inputmode="decimal"
-                            placeholder="100"
-                            value="255"
-                            size=6
-                            on:input=update_with_components
-                            on:change=update_with_components
-                            _ref=component_1_ref
-                        />
-                    </LabeledFloatInput>
-                    <LabeledFloatInput
+                        placeholder="100"
+                    />
+                    <NumberInput
+                        value=component(2)
+                        set_value=set_component(2)
+                        min=0.
+                        max=Signal::derive(move || component_maxes().2)
+                        step=Signal::derive(move || {
+                            component_step(component_maxes().2)
+                        })
+                        full_turn=component_full_turn(2)
+                        decimals=component_decimals
                         prefix=label_2
                         postfix=unit_2
-                    >
-                        <input
-                            type="text"
-                            inputmode="decimal"
-                            placeholder="100"
-                            value="255"
-                            size=6
-                            on:input=update_with_components
-                            on:change=update_with_components
-                            _ref=component_2_ref
-                        />
-                    </LabeledFloatInput>
+                        placeholder="100"
+                    />
                     <CopyButton
                         value=components_copy_string
                     >""</CopyButton>
                 </div>
                 <div class="floats">
-                    <LabeledFloatInput
+                    <NumberInput
+                        value=float(0)
+                        set_value=set_float(0)
+                        min=0.
+                        max=1.
+                        step=0.01
+                        full_turn=float_full_turn(0)
+                        decimals=DECIMAL_PRECISION
+                        min_decimals=1
                         prefix=label_0
                         postfix=None
-                    >
-                        <input
-                            type="text"
-                            inputmode="decimal"
-                            placeholder="1.0"
-                            value="1.0"
-                            size=6
-                            on:input=update_with_floats
-                            on:change=update_with_floats
-                            _ref=float_component_0_ref
-                        />
-                    </LabeledFloatInput>
-                    <LabeledFloatInput
+                        placeholder="1.0"
+                    />
+                    <NumberInput
+                        value=float(1)
+                        set_value=set_float(1)
+                        min=0.
+                        max=1.
+                        step=0.01
+                        full_turn=float_full_turn(1)
+                        decimals=DECIMAL_PRECISION
+                        min_decimals=1
                         prefix=label_1
                         postfix=None
-                    >
-                        <input
-                            type="text"
-                            inputmode="decimal"
-                            placeholder="1.0"
-                            value="1.0"
-                            size=6
-                            on:input=update_with_floats
-                            on:change=update_with_floats
-                            _ref=float_component_1_ref
-                        />
-                    </LabeledFloatInput>
-                    <LabeledFloatInput
+                        placeholder="1.0"
+                    />
+                    <NumberInput
+                        value=float(2)
+                        set_value=set_float(2)
+                        min=0.
+                        max=1.
+                        step=0.01
+                        full_turn=float_full_turn(2)
+                        decimals=DECIMAL_PRECISION
+                        min_decimals=1
                         prefix=label_2
                         postfix=None
-                    >
-                        <input
-                            type="text"
-                            inputmode="decimal"
-                            placeholder="1.0"
-                            size=6
-                            value="1.0"
-                            on:input=update_with_floats
-                            on:change=update_with_floats
-                            _ref=float_component_2_ref
-                        />
-                    </LabeledFloatInput>
+                        placeholder="1.0"
+                    />
                     <CopyButton
                         value=floats_copy_string
                     >""</CopyButton>
                 </div>
+                <div class="locks">
+                    {lock_button(
+                        LockTarget::Component(0),
+                        Signal::derive(move || label_0().unwrap_or_default()),
+                    )}
+                    {lock_button(
+                        LockTarget::Component(1),
+                        Signal::derive(move || label_1().unwrap_or_default()),
+                    )}
+                    {lock_button(
+                        LockTarget::Component(2),
+                        Signal::derive(move || label_2().unwrap_or_default()),
+                    )}
+                    {lock_button(
+                        LockTarget::Luminance,
+                        Signal::derive(|| "Luminance".to_owned()),
+                    )}
+                </div>
             </div>
             <div class="display">
-                <div
-                    class="color-display"
-                    style=color_display_style
-                />
+                <div class="color-display">
+                    <div
+                        class="original"
+                        style=original_style
+                        title=move || {
+                            format!("Original #{}", original().as_hex_code())
+                        }
+                    />
+                    <div class="current" style=color_display_style/>
+                </div>
+                <div class="comparison">
+                    <span title="CIEDE2000 difference to the original">
+                        {delta_e}
+                    </span>
+                    <span title="WCAG contrast ratio to the original">
+                        {contrast}
+                    </span>
+                    <button
+                        title="Revert to the original"
+                        aria-label="Revert to the original"
+                        disabled=is_original
+                        on:click=revert
+                    >
+                        <Icon icon_id="rotate-ccw"/>
+                    </button>
+                    <button
+                        title=pin_label
+                        aria-label=pin_label
+                        aria-pressed=move || original_pinned().to_string()
+                        disabled=move || !original_pinned() && is_original()
+                        on:click=toggle_pin
+                    >
+                        <Icon icon_id="bookmark"/>
+                    </button>
+                </div>
                 // <CopyableLabel
                 //     content=hex_code_hashtag
                 // >
@@ -505,10 +864,20 @@ pub fn ColorPicker() -> impl IntoView {
                     <span class="prefix">"#"</span>
                     <input
                         on:input=on_hex_code_change
+                        on:change=on_hex_code_commit
                         prop:value=hex_code
                         placeholder="000000"
+                        aria-invalid=move || hex_code_invalid().to_string()
+                        aria-errormessage=hex_code_error_id.clone()
                         // value=hex_code.get_untracked()
                     />
+                    <span
+                        class="error-message"
+                        id=hex_code_error_id
+                        hidden=move || !hex_code_invalid()
+                    >
+                        {HEX_CODE_FORMAT}
+                    </span>
 
                     <CopyButton
                         value=hex_code_hashtag
@@ -531,8 +900,37 @@ pub fn ColorPicker() -> impl IntoView {
                     name=Signal::derive( || "color-space".to_owned())
                     on_change=on_color_space_change
                     key="s_color_space"
+                    initial=link.color_space
+                />
+                <StoredRadioGroup
+                    options=geometry_options
+                    title="Picker".to_owned()
+                    name=Signal::derive(|| "picker-geometry".to_owned())
+                    on_change=set_geometry
+                    key="s_picker_geometry"
                 />
             </div>
+            <div class="history">
+                <button
+                    title="Undo (Ctrl+Z)"
+                    aria-label="Undo"
+                    disabled=move || history.with(|history| !history.can_undo())
+                    on:click=move |_| undo()
+                >
+                    <Icon icon_id="corner-up-left"/>
+                </button>
+                <button
+                    title="Redo (Ctrl+Shift+Z)"
+                    aria-label="Redo"
+                    disabled=move || history.with(|history| !history.can_redo())
+                    on:click=move |_| redo()
+                >
+                    <Icon icon_id="corner-up-right"/>
+                </button>
+                <ul class="recent-colors" aria-label="Recent colors">
+                    {move || recent_colors().into_iter().map(recent_color_view).collect_view()}
+                </ul>
+            </div>
             <div class="options">
                 <label>
                     "Precise Inputs"
@@ -546,6 +944,7 @@ pub fn ColorPicker() -> impl IntoView {
                         key="s_precise_inputs"
                         _type=phantom_bool
                         value=precise_inputs
+                        initial=link.precise_inputs
                     />
                 </label>
                 <label class="normalised-inputs">
@@ -560,6 +959,7 @@ pub fn ColorPicker() -> impl IntoView {
                         key="s_normalised_inputs"
                         _type=phantom_bool
                         value=normalised_inputs
+                        initial=link.normalised_inputs
                     />
                 </label>
             </div>
@@ -567,20 +967,30 @@ pub fn ColorPicker() -> impl IntoView {
     }
 }
 
+/// If the event was sent to an input or a textarea.
+fn is_text_field_event(ev: &Event) -> bool {
+    ev.target().is_some_and(|target| {
+        target.is_instance_of::<web_sys::HtmlInputElement>()
+            || target.is_instance_of::<web_sys::HtmlTextAreaElement>()
+    })
+}
+
 #[component]
-pub fn SatValueSurface<S, V>(
+pub fn SatValueSurface<S, V, K>(
     #[prop(into)] sat: Signal<f64>,
     set_sat: S,
     #[prop(into)] value: Signal<f64>,
     set_value: V,
     #[prop(into)] hue: Signal<f64>,
+    /// Called when a key changing the value is released, so that holding it
+    /// down counts as a single change.
+    on_commit: K,
 ) -> impl IntoView
 where
     S: Fn(f64) + Copy + 'static,
     V: Fn(f64) + Copy + 'static,
+    K: Fn() + Copy + 'static,
 {
-    let (dragging, set_dragging) = create_signal(false);
-
     let custom_properties = move || {
         format!(
             "--cursor-x: {}; --cursor-y: {}; --current-hue: {};",
@@ -592,81 +1002,63 @@ where
 
     let surface_ref = create_node_ref::<Div>();
 
-    let on_pointer_move_color = move |ev: &PointerEvent| {
-        // source: https://developer.mozilla.org/en-US/docs/Web/API/MouseEvent/buttons
-        const PRIMARY_BUTTON: u16 = 1;
-
-        let Some(surface_element) = surface_ref.get_untracked() else {
-            log!{"Couldn't find element '.color-picker__color'!"};
-            return;
-        };
-
-        // log!("{}", ev.buttons());
-        if (ev.buttons() & PRIMARY_BUTTON) == 0 {
-            return;
-        }
-
-        let bounds = surface_element.get_bounding_client_rect();
-        let element_x = bounds.left();
-        let element_y = bounds.top();
-
-        let width = surface_element.offset_width() as f64;
-        let height = surface_element.offset_height() as f64;
-        let global_x = ev.client_x() as f64;
-        let global_y = ev.client_y() as f64;
-        let x = ((global_x - element_x) / width).clamp(0., 1.);
-        let y = ((global_y - element_y) / height).clamp(0., 1.);
-
-        set_sat(x);
-        set_value(1. - y);
-    };
-
-    let on_pointer_move = move |ev: &Event| {
-        let ev = ev
-            .dyn_ref::<PointerEvent>()
-            .expect("event wasn't a pointer event");
-
-        if dragging.get_untracked() {
-            on_pointer_move_color(ev);
+    let (on_pointer_down, on_pointer_move, on_pointer_end) =
+        create_surface_drag(surface_ref, move |x, y| {
+            set_sat(x);
+            set_value(1. - y);
+        });
+
+    let on_key_down = move |ev: KeyboardEvent| {
+        const STEP: f64 = 0.01;
+        const COARSE_STEP: f64 = 0.1;
+
+        let step = if ev.shift_key() { COARSE_STEP } else { STEP };
+        let sat_value = sat.get_untracked();
+        let value_value = value.get_untracked();
+        match ev.key().as_str() {
+            "ArrowLeft" => set_sat((sat_value - step).clamp(0., 1.)),
+            "ArrowRight" => set_sat((sat_value + step).clamp(0., 1.)),
+            "ArrowDown" => set_value((value_value - step).clamp(0., 1.)),
+            "ArrowUp" => set_value((value_value + step).clamp(0., 1.)),
+            "Home" => set_sat(0.),
+            "End" => set_sat(1.),
+            "PageDown" => set_value(0.),
+            "PageUp" => set_value(1.),
+            _ => return,
         }
+        ev.prevent_default();
     };
-    // let on_pointer_move_color_closure =
-    // wrap_closure_as_event_listener(on_pointer_move_color);
 
-    let on_pointer_down = move |_: PointerEvent| {
-        set_dragging(true);
-        // window().add_event_listener_with_callback(
-        //     "pointermove",
-        //     on_pointer_move_color_closure.unchecked_ref(),
-        // );
+    // There's no two dimensional slider role, so the saturation is reported
+    // as the value, with both axes in the text.
+    let aria_value_now = move || format!("{:.0}", sat() * 100.);
+    let aria_value_text = move || {
+        format!(
+            "Saturation {:.0}%, Value {:.0}%",
+            sat() * 100.,
+            value() * 100.
+        )
     };
 
-    create_managed_window_event_listener("pointermove", on_pointer_move);
-
-    create_managed_window_event_listener("pointerup", move |_| {
-        set_dragging(false);
-        // window().remove_event_listener_with_callback(
-        //     "pointermove",
-        //     &on_pointer_move_color_closure.unchecked_ref(),
-        // );
-    });
-
-    // store_value(
-    //
-    //     EventListener::new(&window(), "pointermove", on_pointer_move),
-    // );
-    // store_value(
-    //
-    //     EventListener::new(&window(), "pointerup", move |_: &Event| {
-    //         set_dragging(false);
-    //     }),
-    // );
-
     view! {
         <div
             class="sat-value-surface"
             style=custom_properties
+            tabindex="0"
+            role="slider"
+            aria-roledescription="2D slider"
+            aria-label="Saturation and value"
+            aria-valuemin="0"
+            aria-valuemax="100"
+            aria-valuenow=aria_value_now
+            aria-valuetext=aria_value_text
             on:pointerdown=on_pointer_down
+            on:pointermove=on_pointer_move
+            on:pointerup=on_pointer_end
+            on:pointercancel=on_pointer_end
+            on:lostpointercapture=on_pointer_end
+            on:keydown=on_key_down
+            on:keyup=move |_| on_commit()
             _ref=surface_ref
         >
             <div class="sat-value-surface__cursor"/>
@@ -675,104 +1067,2076 @@ where
 }
 
 #[component]
-pub fn HueSlider<F>(#[prop(into)] hue: Signal<f64>, set_hue: F) -> impl IntoView
+pub fn HueSlider<F, K>(
+    #[prop(into)] hue: Signal<f64>,
+    set_hue: F,
+    /// A CSS gradient replacing the HSV hues of the track, for other hues.
+    #[prop(optional, into)]
+    gradient: Option<Signal<String>>,
+    /// Called when a key changing the value is released, so that holding it
+    /// down counts as a single change.
+    on_commit: K,
+) -> impl IntoView
 where
     F: Fn(f64) + Copy + 'static,
+    K: Fn() + Copy + 'static,
 {
-    let (dragging, set_dragging) = create_signal(false);
-
-    let custom_properties = move || format!("--hue: {}", hue());
+    let custom_properties = move || match gradient {
+        Some(gradient) => {
+            format!("--hue: {}; --gradient: {}", hue(), gradient())
+        }
+        None => format!("--hue: {}", hue()),
+    };
 
     let surface_ref = create_node_ref::<Div>();
 
-    let on_pointer_down = move |_: PointerEvent| {
-        // log!("down");
-        set_dragging(true);
+    let (on_pointer_down, on_pointer_move, on_pointer_end) =
+        create_surface_drag(surface_ref, move |x, _| set_hue(x));
+
+    let on_key_down = move |ev: KeyboardEvent| {
+        const STEP: f64 = 1. / 360.;
+        const COARSE_STEP: f64 = 15. / 360.;
+
+        let step = if ev.shift_key() { COARSE_STEP } else { STEP };
+        let hue_value = hue.get_untracked();
+        match ev.key().as_str() {
+            "ArrowLeft" | "ArrowDown" => {
+                set_hue((hue_value - step).clamp(0., 1.))
+            }
+            "ArrowRight" | "ArrowUp" => {
+                set_hue((hue_value + step).clamp(0., 1.))
+            }
+            "Home" => set_hue(0.),
+            "End" => set_hue(1.),
+            _ => return,
+        }
+        ev.prevent_default();
     };
 
-    let on_pointer_move = move |ev: &Event| {
-        // source: https://developer.mozilla.org/en-US/docs/Web/API/MouseEvent/buttons
-        const PRIMARY_BUTTON: u16 = 1;
-
-        // leptos_reactive::SpecialNonReactiveZone::enter();
-
-        // log!("moved {:?}", cx.id());
-
-        if !dragging.get_untracked() {
-            return;
-        }
+    let aria_value_now = move || format!("{:.0}", hue() * 360.);
+    let aria_value_text = move || format!("{:.0}°", hue() * 360.);
 
-        let Some(ev) = ev.dyn_ref::<PointerEvent>() else {
-            log!("incorrect event type");
+    view! {
+        <div
+            class="hue-slider"
+            tabindex="0"
+            role="slider"
+            aria-label="Hue"
+            aria-orientation="horizontal"
+            aria-valuemin="0"
+            aria-valuemax="360"
+            aria-valuenow=aria_value_now
+            aria-valuetext=aria_value_text
+            on:pointerdown=on_pointer_down
+            on:pointermove=on_pointer_move
+            on:pointerup=on_pointer_end
+            on:pointercancel=on_pointer_end
+            on:lostpointercapture=on_pointer_end
+            on:keydown=on_key_down
+            on:keyup=move |_| on_commit()
+            _ref=surface_ref
+            style=custom_properties
+        >
+            <div class="hue-slider__cursor"/>
+        </div>
+    }
+}
 
+/// One slider for every component of the color's color space, with tracks
+/// showing the colors reached by varying that component.
+#[component]
+pub fn ComponentSliders<F, K>(
+    #[prop(into)] color: Signal<DynamicColor>,
+    set_color: F,
+    /// Called when a key changing the value is released, so that holding it
+    /// down counts as a single change.
+    on_commit: K,
+) -> impl IntoView
+where
+    F: Fn(DynamicColor) + Copy + 'static,
+    K: Fn() + Copy + 'static,
+{
+    let color_space = create_memo(move |_| color().color_space());
+
+    move || {
+        let labels = color_space().info().labels;
+        [labels.0, labels.1, labels.2]
+            .into_iter()
+            .enumerate()
+            .map(|(index, label)| {
+                view! {
+                    <ComponentSlider
+                        label=label
+                        index=index
+                        color=color
+                        set_color=set_color
+                        on_commit=on_commit
+                    />
+                }
+            })
+            .collect_view()
+    }
+}
+
+#[component]
+fn ComponentSlider<F, K>(
+    label: &'static str,
+    /// Which component of the color the slider changes.
+    index: usize,
+    #[prop(into)] color: Signal<DynamicColor>,
+    set_color: F,
+    /// Called when a key changing the value is released, so that holding it
+    /// down counts as a single change.
+    on_commit: K,
+) -> impl IntoView
+where
+    F: Fn(DynamicColor) + Copy + 'static,
+    K: Fn() + Copy + 'static,
+{
+    /// How many colors the track gradient is made of.
+    const GRADIENT_STEPS: usize = 24;
+
+    let float = Signal::derive(move || color().float(index));
+    let set_float = move |float: f64| {
+        set_color(color.get_untracked().set_float(index, float))
+    };
+
+    let custom_properties = move || {
+        let stops = color()
+            .component_gradient(index, GRADIENT_STEPS)
+            .into_iter()
+            .map(|rgb| format!("#{}", rgb.as_hex_code()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("--value: {}; --gradient: {};", float(), stops)
+    };
+
+    let surface_ref = create_node_ref::<Div>();
+
+    let (on_pointer_down, on_pointer_move, on_pointer_end) =
+        create_surface_drag(surface_ref, move |x, _| set_float(x));
+
+    let on_key_down = move |ev: KeyboardEvent| {
+        const STEP: f64 = 0.01;
+        const COARSE_STEP: f64 = 0.1;
+
+        let step = if ev.shift_key() { COARSE_STEP } else { STEP };
+        let float = float.get_untracked();
+        match ev.key().as_str() {
+            "ArrowLeft" | "ArrowDown" => {
+                set_float((float - step).clamp(0., 1.))
+            }
+            "ArrowRight" | "ArrowUp" => set_float((float + step).clamp(0., 1.)),
+            "Home" => set_float(0.),
+            "End" => set_float(1.),
+            _ => return,
+        }
+        ev.prevent_default();
+    };
+
+    let aria_value_now = move || format!("{:.0}", float() * 100.);
+    let aria_value_text = move || format!("{:.0}%", float() * 100.);
+
+    view! {
+        <div class="component-slider">
+            <span class="label" aria-hidden="true">
+                {label}
+            </span>
+            <div
+                class="track"
+                tabindex="0"
+                role="slider"
+                aria-label=label
+                aria-orientation="horizontal"
+                aria-valuemin="0"
+                aria-valuemax="100"
+                aria-valuenow=aria_value_now
+                aria-valuetext=aria_value_text
+                on:pointerdown=on_pointer_down
+                on:pointermove=on_pointer_move
+                on:pointerup=on_pointer_end
+                on:pointercancel=on_pointer_end
+                on:lostpointercapture=on_pointer_end
+                on:keydown=on_key_down
+                on:keyup=move |_| on_commit()
+                _ref=surface_ref
+                style=custom_properties
+            >
+                <div class="cursor"/>
+            </div>
+        </div>
+    }
+}
+
+/// A picker geometry other than the square, drawn to a canvas.
+#[component]
+fn GeometrySurface<H, C, K>(
+    geometry: PickerGeometry,
+    /// The hue the picker is drawn with, in turns.
+    #[prop(into)]
+    hue: Signal<f64>,
+    set_hue: H,
+    /// The position of the cursor on the inner part.
+    #[prop(into)]
+    cursor: Signal<(f64, f64)>,
+    /// Called with the points the inner part is dragged to, which may be
+    /// outside of it.
+    set_cursor: C,
+    /// A description of the color at the cursor, for screen readers.
+    #[prop(into)]
+    value_text: Signal<String>,
+    /// Called when a key changing the value is released, so that holding it
+    /// down counts as a single change.
+    on_commit: K,
+) -> impl IntoView
+where
+    H: Fn(f64) + Copy + 'static,
+    C: Fn(f64, f64) + Copy + 'static,
+    K: Fn() + Copy + 'static,
+{
+    /// The size of the drawn image in pixels, before it's scaled to fit.
+    const RENDER_SIZE: u32 = 256;
+
+    let canvas_ref = create_node_ref::<Canvas>();
+    create_effect(move |_| {
+        let Some(canvas) = canvas_ref.get() else {
             return;
         };
+        let image = geometry.render(hue(), RENDER_SIZE);
+        draw_rgba_image(&canvas, image.width(), image.height(), image.data());
+    });
 
-        if (ev.buttons() & PRIMARY_BUTTON) == 0 {
-            return;
+    let surface_ref = create_node_ref::<Div>();
+
+    // The part the drag started on keeps being dragged, even when the pointer
+    // leaves it.
+    let dragged_part = store_value::<Option<PickerPart>>(None);
+    let (on_pointer_down, on_pointer_move, on_pointer_end) =
+        create_surface_drag(surface_ref, move |x, y| {
+            if dragged_part.get_value().is_none() {
+                dragged_part.set_value(geometry.part_at(x, y));
+            }
+            match dragged_part.get_value() {
+                Some(PickerPart::HueRing) => set_hue(hue_at(x, y)),
+                Some(PickerPart::Inner) => set_cursor(x, y),
+                None => {}
+            }
+        });
+    let on_pointer_down = move |ev: PointerEvent| {
+        dragged_part.set_value(None);
+        on_pointer_down(ev);
+    };
+
+    let on_key_down = move |ev: KeyboardEvent| {
+        const STEP: f64 = 0.01;
+        const COARSE_STEP: f64 = 0.1;
+        const HUE_STEP: f64 = 1. / 360.;
+        const HUE_COARSE_STEP: f64 = 15. / 360.;
+
+        let coarse = ev.shift_key();
+        let step = if coarse { COARSE_STEP } else { STEP };
+        let hue_step = if coarse { HUE_COARSE_STEP } else { HUE_STEP };
+        let (x, y) = cursor.get_untracked();
+        match ev.key().as_str() {
+            "ArrowLeft" => set_cursor(x - step, y),
+            "ArrowRight" => set_cursor(x + step, y),
+            "ArrowUp" => set_cursor(x, y - step),
+            "ArrowDown" => set_cursor(x, y + step),
+            "PageUp" => {
+                set_hue((hue.get_untracked() + hue_step).rem_euclid(1.))
+            }
+            "PageDown" => {
+                set_hue((hue.get_untracked() - hue_step).rem_euclid(1.))
+            }
+            _ => return,
         }
+        ev.prevent_default();
+    };
 
-        let Some(surface_element) = surface_ref.get() else {
-            log!{"Couldn't find element '.hue-slider'!"};
+    let custom_properties = move || {
+        let (x, y) = cursor();
+        let (hue_x, hue_y) = hue_position(hue());
+        format!(
+            "--cursor-x: {}; --cursor-y: {}; --hue-x: {}; --hue-y: {};",
+            x, y, hue_x, hue_y,
+        )
+    };
+
+    view! {
+        <div
+            class="geometry-surface"
+            style=custom_properties
+            tabindex="0"
+            role="slider"
+            aria-roledescription="2D slider"
+            aria-label=geometry.to_ui_string(())
+            aria-valuemin="0"
+            aria-valuemax="100"
+            aria-valuenow=move || format!("{:.0}", cursor().0 * 100.)
+            aria-valuetext=value_text
+            aria-keyshortcuts="PageUp PageDown"
+            on:pointerdown=on_pointer_down
+            on:pointermove=on_pointer_move
+            on:pointerup=on_pointer_end
+            on:pointercancel=on_pointer_end
+            on:lostpointercapture=on_pointer_end
+            on:keydown=on_key_down
+            on:keyup=move |_| on_commit()
+            _ref=surface_ref
+        >
+            <canvas class="image" _ref=canvas_ref/>
+            <Show when=move || geometry.has_hue_ring()>
+                <div class="hue-cursor"/>
+            </Show>
+            <div class="cursor"/>
+        </div>
+    }
+}
+
+/// Create the pointer event handlers for dragging over a surface, which call
+/// `on_drag` with the position of the pointer relative to the surface, in the
+/// range 0 to 1 on both axes.
+///
+/// The dragging pointer is captured by the surface, so that the drag continues
+/// outside of it without any window listeners. Only one pointer drags at a
+/// time, other touches on the same surface are ignored until it's released.
+///
+/// Returns the handlers for `pointerdown`, `pointermove` and the events ending
+/// the drag: `pointerup`, `pointercancel` and `lostpointercapture`.
+fn create_surface_drag<F>(
+    surface_ref: NodeRef<Div>,
+    on_drag: F,
+) -> (
+    impl Fn(PointerEvent) + Copy + 'static,
+    impl Fn(PointerEvent) + Copy + 'static,
+    impl Fn(PointerEvent) + Copy + 'static,
+)
+where
+    F: Fn(f64, f64) + Copy + 'static,
+{
+    // source: https://developer.mozilla.org/en-US/docs/Web/API/MouseEvent/button
+    const PRIMARY_BUTTON: i16 = 0;
+
+    let dragging_pointer = store_value::<Option<i32>>(None);
+
+    let drag_to = move |ev: &PointerEvent| {
+        let Some(surface_element) = surface_ref.get_untracked() else {
             return;
         };
 
         let bounds = surface_element.get_bounding_client_rect();
-        let element_x = bounds.left();
+        let x = ((ev.client_x() as f64 - bounds.left()) / bounds.width())
+            .clamp(0., 1.);
+        let y = ((ev.client_y() as f64 - bounds.top()) / bounds.height())
+            .clamp(0., 1.);
+
+        // Empty surfaces would divide by zero.
+        if x.is_finite() && y.is_finite() {
+            on_drag(x, y);
+        }
+    };
 
-        let width = surface_element.offset_width() as f64;
-        let global_x = ev.client_x() as f64;
-        let x = ((global_x - element_x) / width).clamp(0., 1.);
+    let on_pointer_down = move |ev: PointerEvent| {
+        if ev.button() != PRIMARY_BUTTON
+            || dragging_pointer.get_value().is_some()
+        {
+            return;
+        }
+        let Some(surface_element) = surface_ref.get_untracked() else {
+            return;
+        };
 
-        set_hue(x);
+        if surface_element
+            .set_pointer_capture(ev.pointer_id())
+            .is_err()
+        {
+            return;
+        }
+        dragging_pointer.set_value(Some(ev.pointer_id()));
+        drag_to(&ev);
     };
 
-    let on_pointer_up = move |_: &_| {
-        // log!("up");
-        set_dragging(false);
+    let on_pointer_move = move |ev: PointerEvent| {
+        if dragging_pointer.get_value() == Some(ev.pointer_id()) {
+            drag_to(&ev);
+        }
     };
 
-    // let listener = EventListener::new(&window(), "pointermove", move |ev| {
-    //     on_pointer_move(ev);
-    // });
-    // listener.forget();
+    let on_pointer_end = move |ev: PointerEvent| {
+        if dragging_pointer.get_value() != Some(ev.pointer_id()) {
+            return;
+        }
+        dragging_pointer.set_value(None);
+        if let Some(surface_element) = surface_ref.get_untracked() {
+            let _ = surface_element.release_pointer_capture(ev.pointer_id());
+        }
+    };
 
-    // TODO: These event listeners are not destroyed on element cleanup.
-    create_managed_window_event_listener("pointerup", on_pointer_up);
-    create_managed_window_event_listener("pointermove", on_pointer_move);
+    (on_pointer_down, on_pointer_move, on_pointer_end)
+}
 
-    // create_managed_window_event_listener( "pointerup", move |_| {
-    //     log!("up");
-    // });
+/// Converts the color through an uploaded ICC profile, showing the device
+/// values a printer or monitor with that profile would use for it.
+#[component]
+pub fn IccProfileConverter(
+    #[prop(into)] color: Signal<DynamicColor>,
+) -> impl IntoView {
+    let intent_options = vec![
+        ("Perceptual".to_owned(), RenderingIntent::Perceptual),
+        ("Relative".to_owned(), RenderingIntent::RelativeColorimetric),
+        ("Saturation".to_owned(), RenderingIntent::Saturation),
+        ("Absolute".to_owned(), RenderingIntent::AbsoluteColorimetric),
+    ];
 
-    // create_managed_event_listener( );
+    let (intent, set_intent) =
+        create_signal(RenderingIntent::RelativeColorimetric);
 
-    // _ = window().focus();
+    let profile = create_rw_signal::<Option<IccProfile>>(None);
+    let (load_error, set_load_error) = create_signal::<Option<String>>(None);
 
-    // if is_browser() {
-    //     store_value(
-    //
-    //         EventListener::new(&window(), "pointerup", move |_| {
-    //             log!("up");
-    //             set_dragging(false);
-    //         }),
-    //     );
-    // }
-    // store_value(
-    //
-    //     EventListener::new(&window(), "pointermove", on_pointer_move),
-    // );
+    let on_file_change = move |ev: Event| {
+        let file_input = event_target::<web_sys::HtmlInputElement>(&ev);
+        let Some(file) = file_input.files().and_then(|files| files.get(0))
+        else {
+            return;
+        };
+
+        read_file_bytes(&file, move |bytes| match IccProfile::parse(&bytes) {
+            Ok(parsed) => {
+                profile.set(Some(parsed));
+                set_load_error(None);
+            }
+            Err(reason) => {
+                profile.set(None);
+                set_load_error(Some(format!(
+                    "Couldn't load the profile: {}.",
+                    reason
+                )));
+            }
+        });
+    };
+
+    let conversion = create_memo(move |_| {
+        let color = color();
+        let intent = intent();
+        profile.with(move |profile| {
+            let profile = profile.as_ref()?;
+
+            let result: Result<_, IccError> = try {
+                let device = color.to_icc_device_values(profile, intent)?;
+                let proof = profile.device_to_rgb(&device, intent)?;
+                (profile.color_space(), device, proof)
+            };
+            Some(result.map_err(|reason| reason.to_string()))
+        })
+    });
+
+    let profile_info = move || {
+        profile.with(|profile| {
+            profile.as_ref().map(|profile| {
+                let (major, minor) = profile.version();
+                format!(
+                    "{} ({} {}, v{}.{})",
+                    profile.description().unwrap_or("Unnamed profile"),
+                    profile.color_space().to_ui_string(()),
+                    profile.device_class(),
+                    major,
+                    minor
+                )
+            })
+        })
+    };
+
+    let format_device_value = |space: IccColorSpace, value: f64| match space {
+        IccColorSpace::Rgb => naturally_format_float(value * 255., 0, 0),
+        _ => format!("{}%", naturally_format_float(value * 100., 0, 1)),
+    };
 
-    // window().event
+    let device_values_copy_string = Signal::derive(move || {
+        let Some(Ok((space, device, _))) = conversion() else {
+            return "".to_owned();
+        };
+        device
+            .iter()
+            .map(|value| format_device_value(space, *value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    });
+
+    let result_view = move || {
+        let (space, device, proof) = match conversion()? {
+            Ok(result) => result,
+            Err(reason) => {
+                return Some(
+                    view! { <p class="error">{reason}</p> }.into_view(),
+                );
+            }
+        };
+
+        let values = space
+            .channel_labels()
+            .into_iter()
+            .zip(device)
+            .map(|(label, value)| {
+                view! {
+                    <li>
+                        <span class="label">{label}</span>
+                        {format_device_value(space, value)}
+                    </li>
+                }
+            })
+            .collect_view();
+
+        let proof_style = proof.as_css_variables();
+
+        Some(
+            view! {
+                <div class="result">
+                    <ul class="device-values">{values}</ul>
+                    <CopyButton
+                        value=device_values_copy_string
+                        label=Some("Copy device values".to_owned())
+                    />
+                    <div
+                        class="proof"
+                        style=proof_style
+                        title="The profile color converted back to sRGB"
+                    />
+                </div>
+            }
+            .into_view(),
+        )
+    };
 
     view! {
-        <div
-            class="hue-slider"
-            on:pointerdown=on_pointer_down
-            _ref=surface_ref
-            style=custom_properties
+        <section class="tool-panel icc-converter">
+            <h2>"ICC Profile Conversion"</h2>
+            <label class="file-input">
+                "Profile"
+                <input
+                    type="file"
+                    accept=".icc,.icm"
+                    on:change=on_file_change
+                />
+            </label>
+            <p class="info">{profile_info}</p>
+            {move || {
+                load_error().map(|reason| view! { <p class="error">{reason}</p> })
+            }}
+            <StoredRadioGroup
+                options=intent_options
+                title="Rendering Intent".to_owned()
+                name=Signal::derive(|| "icc-rendering-intent".to_owned())
+                on_change=set_intent
+                key="s_icc_rendering_intent"
+            />
+            {result_view}
+        </section>
+    }
+}
+
+/// Displays the nearest Munsell notation of the color, and lets the user enter
+/// a notation to load.
+#[component]
+pub fn MunsellNotation(color: RwSignal<DynamicColor>) -> impl IntoView {
+    let notation = create_memo(move |_| {
+        Munsell::from_rgb(color().to_color::<Rgb>())
+            .map(|munsell| munsell.to_string())
+    });
+
+    let (input_error, set_input_error) = create_signal::<Option<String>>(None);
+
+    let on_notation_change = move |ev: Event| {
+        let value = event_target_value(&ev);
+
+        let Ok(munsell) = value.parse::<Munsell>() else {
+            set_input_error(Some(
+                "Expected a notation like '5R 4/14' or 'N 5/'.".to_owned(),
+            ));
+            return;
+        };
+        let Some(rgb) = munsell.to_rgb() else {
+            set_input_error(Some(
+                "That notation is outside of the renotation data.".to_owned(),
+            ));
+            return;
+        };
+
+        set_input_error(None);
+        color.set(
+            DynamicColor::from_color(rgb)
+                .set_color_space(color.get_untracked().color_space()),
+        );
+    };
+
+    let notation_copy_string =
+        Signal::derive(move || notation().unwrap_or_default());
+
+    view! {
+        <section class="tool-panel munsell-notation">
+            <h2>"Munsell Notation"</h2>
+            <div class="notation">
+                <input
+                    type="text"
+                    placeholder="5R 4/14"
+                    aria-label="Munsell notation"
+                    prop:value=move || notation().unwrap_or_default()
+                    on:change=on_notation_change
+                />
+                <CopyButton value=notation_copy_string/>
+            </div>
+            {move || {
+                notation().is_none().then(|| view! {
+                    <p class="info">
+                        "This color is outside of the renotation data."
+                    </p>
+                })
+            }}
+            {move || {
+                input_error().map(|reason| view! { <p class="error">{reason}</p> })
+            }}
+        </section>
+    }
+}
+
+/// Shows color harmonies generated from the color. Clicking a swatch loads its
+/// color.
+#[component]
+pub fn ColorHarmonies(color: RwSignal<DynamicColor>) -> impl IntoView {
+    let hue_space_options = vec![
+        ("HSL".to_owned(), ColorSpace::Hsl),
+        ("HSV".to_owned(), ColorSpace::Hsv),
+        ("OKLCh".to_owned(), ColorSpace::Oklch),
+    ];
+
+    let (hue_space, set_hue_space) = create_signal(ColorSpace::Hsl);
+
+    let swatch_view = move |swatch: DynamicColor| {
+        let rgb = swatch.to_color::<Rgb>();
+        let hex_code = format!("#{}", rgb.as_hex_code());
+        let style = rgb.as_css_variables();
+
+        view! {
+            <li class="swatch">
+                <button
+                    class="color"
+                    style=style
+                    title=format!("Load {}", hex_code)
+                    aria-label=format!("Load {}", hex_code)
+                    on:click=move |_| color.set(swatch)
+                />
+                <CopyButton value=Signal::derive({
+                    let hex_code = hex_code.clone();
+                    move || hex_code.clone()
+                })>
+                    {hex_code.clone()}
+                </CopyButton>
+            </li>
+        }
+    };
+
+    let harmonies_view = move || {
+        let base = color();
+        let hue_space = hue_space();
+
+        Harmony::ALL
+            .into_iter()
+            .filter_map(|harmony| {
+                let swatches = harmony
+                    .generate(base, hue_space)?
+                    .into_iter()
+                    .map(swatch_view)
+                    .collect_view();
+
+                Some(view! {
+                    <li class="harmony">
+                        <h3>{harmony.to_ui_string(())}</h3>
+                        <ul class="swatches">{swatches}</ul>
+                    </li>
+                })
+            })
+            .collect_view()
+    };
+
+    view! {
+        <section class="tool-panel color-harmonies">
+            <h2>"Harmonies"</h2>
+            <StoredRadioGroup
+                options=hue_space_options
+                title="Hue Space".to_owned()
+                name=Signal::derive(|| "harmony-hue-space".to_owned())
+                on_change=set_hue_space
+                key="s_harmony_hue_space"
+            />
+            <ul class="harmonies">{harmonies_view}</ul>
+        </section>
+    }
+}
+
+/// Generates a 50 to 950 tint and shade scale from the color, and exports it as
+/// design tokens.
+#[component]
+pub fn ColorScale(#[prop(into)] color: Signal<DynamicColor>) -> impl IntoView {
+    let space_options = vec![
+        ("OKLCh".to_owned(), ScaleSpace::Oklch),
+        ("LCh".to_owned(), ScaleSpace::Lch),
+    ];
+    let format_options = vec![
+        ("CSS".to_owned(), ScaleFormat::Css),
+        ("Tailwind".to_owned(), ScaleFormat::Tailwind),
+        ("SCSS".to_owned(), ScaleFormat::Scss),
+        ("JSON".to_owned(), ScaleFormat::Json),
+    ];
+
+    let (space, set_space) = create_signal(ScaleOptions::default().space);
+    let (format, set_format) = create_signal(ScaleFormat::Css);
+    let (curve, set_curve) = create_signal(ScaleOptions::default().curve);
+    let (chroma_easing, set_chroma_easing) =
+        create_signal(ScaleOptions::default().chroma_easing);
+    let (name, set_name) = create_signal("primary".to_owned());
+
+    let scale = create_memo(move |_| {
+        let options = ScaleOptions {
+            space: space(),
+            curve: curve(),
+            chroma_easing: chroma_easing(),
+            ..Default::default()
+        };
+        generate_scale(color().to_color::<Rgb>(), &options)
+    });
+
+    let exported = Signal::derive(move || {
+        name.with(|name| scale.with(|scale| format().format(name, scale)))
+    });
+
+    let swatches_view = move || {
+        scale()
+            .into_iter()
+            .map(|(step, rgb)| {
+                let hex_code = format!("#{}", rgb.as_hex_code());
+                let style = rgb.as_css_variables();
+
+                view! {
+                    <li class="swatch">
+                        <div class="color" style=style/>
+                        <span class="step">{step}</span>
+                        <CopyButton value=Signal::derive({
+                            let hex_code = hex_code.clone();
+                            move || hex_code.clone()
+                        })>
+                            {hex_code.clone()}
+                        </CopyButton>
+                    </li>
+                }
+            })
+            .collect_view()
+    };
+
+    let on_float_input = |set_value: WriteSignal<f64>| {
+        move |ev: Event| {
+            if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                set_value(value);
+            }
+        }
+    };
+
+    view! {
+        <section class="tool-panel color-scale">
+            <h2>"Scale"</h2>
+            <div class="options">
+                <label>
+                    "Name"
+                    <input
+                        type="text"
+                        prop:value=name
+                        on:input=move |ev| set_name(event_target_value(&ev))
+                    />
+                </label>
+                <label>
+                    "Lightness Curve"
+                    <input
+                        type="range"
+                        min="0.3"
+                        max="3"
+                        step="0.05"
+                        prop:value=curve
+                        on:input=on_float_input(set_curve)
+                    />
+                </label>
+                <label>
+                    "Chroma Easing"
+                    <input
+                        type="range"
+                        min="0"
+                        max="1"
+                        step="0.05"
+                        prop:value=chroma_easing
+                        on:input=on_float_input(set_chroma_easing)
+                    />
+                </label>
+            </div>
+            <StoredRadioGroup
+                options=space_options
+                title="Interpolation Space".to_owned()
+                name=Signal::derive(|| "scale-space".to_owned())
+                on_change=set_space
+                key="s_scale_space"
+            />
+            <ul class="swatches">{swatches_view}</ul>
+            <StoredRadioGroup
+                options=format_options
+                title="Export Format".to_owned()
+                name=Signal::derive(|| "scale-format".to_owned())
+                on_change=set_format
+                key="s_scale_format"
+            />
+            <div class="export">
+                <textarea readonly rows="8" prop:value=exported/>
+                <CopyButton
+                    value=exported
+                    label=Some("Copy exported scale".to_owned())
+                />
+            </div>
+        </section>
+    }
+}
+
+/// Mixes two colors like CSS `color-mix()` does, to preview what the browser
+/// will render.
+#[component]
+pub fn ColorMixer(color: RwSignal<DynamicColor>) -> impl IntoView {
+    let space_options = InterpolationSpace::ALL
+        .into_iter()
+        .map(|space| (space.to_ui_string(()), space))
+        .collect::<Vec<_>>();
+    let hue_options = vec![
+        ("Shorter".to_owned(), HueInterpolation::Shorter),
+        ("Longer".to_owned(), HueInterpolation::Longer),
+        ("Increasing".to_owned(), HueInterpolation::Increasing),
+        ("Decreasing".to_owned(), HueInterpolation::Decreasing),
+    ];
+
+    let (space, set_space) = create_signal(InterpolationSpace::Oklab);
+    let (hue_interpolation, set_hue_interpolation) =
+        create_signal(HueInterpolation::Shorter);
+    let (percentage, set_percentage) = create_signal(50.);
+
+    let (code_a, set_code_a) = create_signal(format!(
+        "#{}",
+        color.get_untracked().to_color::<Rgb>().as_hex_code()
+    ));
+    let (code_b, set_code_b) = create_signal("#000000".to_owned());
+
+    let parsed_a =
+        move || code_a.with(|code| Rgb::from_hex_code_with_alpha(code));
+    let parsed_b =
+        move || code_b.with(|code| Rgb::from_hex_code_with_alpha(code));
+
+    let mixed = create_memo(move |_| {
+        let (rgb_a, alpha_a) = parsed_a()?;
+        let (rgb_b, alpha_b) = parsed_b()?;
+
+        let (mixed, alpha) = color_mix(
+            MixColor {
+                color: DynamicColor::from_color(rgb_a),
+                alpha: alpha_a,
+                percentage: None,
+            },
+            MixColor {
+                color: DynamicColor::from_color(rgb_b),
+                alpha: alpha_b,
+                percentage: Some(percentage()),
+            },
+            space(),
+            hue_interpolation(),
+        )?;
+        Some((mixed.to_color::<Rgb>(), alpha))
+    });
+
+    let css = Signal::derive(move || {
+        format!(
+            "color-mix({}, {}, {} {}%)",
+            interpolation_method_css(space(), hue_interpolation()),
+            code_a(),
+            code_b(),
+            naturally_format_float(percentage(), 0, 1)
+        )
+    });
+    let mixed_code = Signal::derive(move || {
+        mixed().map_or("".to_owned(), |(rgb, alpha)| {
+            format!("#{}", rgb.as_hex_code_with_alpha(alpha))
+        })
+    });
+
+    let swatch_style = |rgb: Rgb, alpha: f64| {
+        format!("{} --a: {};", rgb.as_css_variables(), alpha)
+    };
+
+    let code_input = move |label: &'static str,
+                           code: ReadSignal<String>,
+                           set_code: WriteSignal<String>| {
+        let parsed =
+            move || code.with(|code| Rgb::from_hex_code_with_alpha(code));
+
+        view! {
+            <label class="mix-input">
+                {label}
+                <div
+                    class="swatch"
+                    style=move || parsed().map(|(rgb, alpha)| swatch_style(rgb, alpha))
+                />
+                <input
+                    type="text"
+                    prop:value=code
+                    aria-invalid=move || parsed().is_none().to_string()
+                    on:change=move |ev| set_code(event_target_value(&ev))
+                />
+            </label>
+        }
+    };
+
+    let load_mixed = move |_| {
+        let Some((rgb, _)) = mixed() else {
+            return;
+        };
+        color.set(
+            DynamicColor::from_color(rgb)
+                .set_color_space(color.get_untracked().color_space()),
+        );
+    };
+
+    view! {
+        <section class="tool-panel color-mixer">
+            <h2>"Mix"</h2>
+            <div class="inputs">
+                {code_input("First", code_a, set_code_a)}
+                <label class="ratio">
+                    {move || format!("{}%", naturally_format_float(percentage(), 0, 1))}
+                    <input
+                        type="range"
+                        min="0"
+                        max="100"
+                        step="1"
+                        prop:value=percentage
+                        on:input=move |ev| {
+                            if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                                set_percentage(value);
+                            }
+                        }
+                    />
+                </label>
+                {code_input("Second", code_b, set_code_b)}
+            </div>
+            <StoredRadioGroup
+                options=space_options
+                title="Color Space".to_owned()
+                name=Signal::derive(|| "mix-space".to_owned())
+                on_change=set_space
+                key="s_mix_space"
+            />
+            <StoredRadioGroup
+                options=hue_options
+                title="Hue Path".to_owned()
+                name=Signal::derive(|| "mix-hue".to_owned())
+                on_change=set_hue_interpolation
+                key="s_mix_hue"
+            />
+            {move || match mixed() {
+                Some((rgb, alpha)) => view! {
+                    <div class="result">
+                        <div class="swatch" style=swatch_style(rgb, alpha)/>
+                        <CopyButton value=mixed_code>{mixed_code}</CopyButton>
+                        <button class="load" on:click=load_mixed>
+                            "Load"
+                        </button>
+                    </div>
+                }
+                .into_view(),
+                None => view! {
+                    <p class="error">"Enter colors as hex codes, like #ff8800 or #ff880080."</p>
+                }
+                .into_view(),
+            }}
+            <div class="css">
+                <code>{css}</code>
+                <CopyButton value=css label=Some("Copy CSS".to_owned())/>
+            </div>
+        </section>
+    }
+}
+
+/// The user's saved palettes. Clicking a swatch loads its color, and swatches
+/// can be reordered by dragging them.
+#[component]
+pub fn Palettes(
+    color: RwSignal<DynamicColor>,
+    /// The palettes, which can be shared with other panels that add to them.
+    /// It's loaded from and saved to local storage by this component.
+    #[prop(optional)]
+    library: Option<RwSignal<PaletteLibrary>>,
+) -> impl IntoView {
+    let library =
+        library.unwrap_or_else(|| create_rw_signal(PaletteLibrary::default()));
+    let (loaded, set_loaded) = create_signal(false);
+
+    // Local storage only exists in the browser, so the library is loaded after
+    // hydrating.
+    create_effect(move |_| {
+        if let Some(stored) =
+            local_storage_value::<PaletteLibrary>(PALETTE_LIBRARY_KEY)
+        {
+            library.set(stored);
+        }
+        set_loaded(true);
+    });
+    create_effect(move |_| {
+        let library = library.get();
+        if !loaded.get_untracked() {
+            return;
+        }
+        if local_storage_set_value(PALETTE_LIBRARY_KEY, library).is_none() {
+            error!("Failed to store palette library.");
+        }
+    });
+
+    // The palette and swatch ids of the swatch being dragged.
+    let dragged = create_rw_signal::<Option<(u64, u64)>>(None);
+
+    // The ids of the swatches selected for adjusting. The adjustments are
+    // previewed on them until they're applied.
+    let selected = create_rw_signal(HashSet::<u64>::new());
+    let adjustments = create_rw_signal(Adjustments::default());
+    let (adjustment_space, set_adjustment_space) =
+        create_signal(ColorSpace::Oklch);
+    let adjustment_space_options = vec![
+        ("HSL".to_owned(), ColorSpace::Hsl),
+        ("HSV".to_owned(), ColorSpace::Hsv),
+        ("OKLCh".to_owned(), ColorSpace::Oklch),
+    ];
+
+    let is_selected = move |swatch_id: u64| {
+        selected.with(|selected| selected.contains(&swatch_id))
+    };
+    let toggle_selected = move |swatch_id: u64| {
+        selected.update(|selected| {
+            if !selected.remove(&swatch_id) {
+                selected.insert(swatch_id);
+            }
+        });
+    };
+    let selected_count = create_memo(move |_| {
+        selected.with(|selected| {
+            library.with(|library| {
+                library
+                    .palettes
+                    .iter()
+                    .flat_map(|palette| &palette.swatches)
+                    .filter(|swatch| selected.contains(&swatch.id))
+                    .count()
+            })
+        })
+    });
+    let adjust = |rgb: Rgb, adjustments: Adjustments, space: ColorSpace| {
+        adjustments
+            .apply(DynamicColor::from_color(rgb), space)
+            .map_or(rgb, |color| color.to_color::<Rgb>())
+    };
+    let apply_adjustments = move |_| {
+        let space = adjustment_space.get_untracked();
+        let applied = adjustments.get_untracked();
+        selected.with_untracked(|selected| {
+            library.update(|library| {
+                let swatches = library
+                    .palettes
+                    .iter_mut()
+                    .flat_map(|palette| &mut palette.swatches)
+                    .filter(|swatch| selected.contains(&swatch.id));
+                for swatch in swatches {
+                    swatch.color = adjust(swatch.color, applied, space);
+                }
+            });
+        });
+        adjustments.set(Adjustments::default());
+    };
+    let clear_selection = move |_| {
+        selected.update(HashSet::clear);
+        adjustments.set(Adjustments::default());
+    };
+
+    let update_palette = move |id: u64, update: &dyn Fn(&mut Palette)| {
+        library.update(|library| {
+            if let Some(palette) = library.palette_mut(id) {
+                update(palette);
+            }
+        });
+    };
+
+    let add_palette = move |_| {
+        library.update(|library| {
+            let name = format!("Palette {}", library.palettes.len() + 1);
+            library.add_palette(name);
+        });
+    };
+
+    let format_options = PaletteFormat::ALL
+        .into_iter()
+        .map(|format| (format.to_ui_string(()), format))
+        .collect::<Vec<_>>();
+    let (export_format, set_export_format) = create_signal(PaletteFormat::Gpl);
+    let (import_error, set_import_error) =
+        create_signal::<Option<String>>(None);
+
+    let import_files = move |files: web_sys::FileList| {
+        set_import_error(None);
+        for index in 0..files.length() {
+            let Some(file) = files.get(index) else {
+                continue;
+            };
+            let file_name = file.name();
+            read_file_bytes(&file, move |bytes| {
+                let format = PaletteFormat::detect(&file_name, &bytes);
+                let fallback_name = file_name
+                    .rsplit_once('.')
+                    .map_or(file_name.as_str(), |(name, _)| name);
+                match format.read(&bytes, fallback_name) {
+                    Ok(palette) => {
+                        library.update(|library| library.palettes.push(palette))
+                    }
+                    Err(err) => set_import_error(Some(format!(
+                        "Couldn't import '{}': {}.",
+                        file_name, err
+                    ))),
+                }
+            });
+        }
+    };
+
+    let on_file_change = move |ev: Event| {
+        let file_input = event_target::<web_sys::HtmlInputElement>(&ev);
+        if let Some(files) = file_input.files() {
+            import_files(files);
+        }
+        // Allow importing the same file again.
+        file_input.set_value("");
+    };
+
+    // Files can be dropped anywhere on the panel. Swatches being reordered are
+    // handled by the swatches themselves.
+    let on_files_drag_over = move |ev: DragEvent| {
+        if dragged().is_none() {
+            ev.prevent_default();
+        }
+    };
+    let on_files_drop = move |ev: DragEvent| {
+        if dragged().is_some() {
+            return;
+        }
+        let Some(files) = ev.data_transfer().and_then(|data| data.files())
+        else {
+            return;
+        };
+        if files.length() > 0 {
+            ev.prevent_default();
+            import_files(files);
+        }
+    };
+
+    let swatch_view = move |palette_id: u64, swatch: Swatch| {
+        let swatch_id = swatch.id;
+        let rgb = swatch.color;
+        let hex_code = format!("#{}", rgb.as_hex_code());
+        let label = match &swatch.name {
+            Some(name) => format!("{} ({})", name, hex_code),
+            None => hex_code.clone(),
+        };
+        // Selected swatches preview the adjustments.
+        let style = move || {
+            let rgb = match is_selected(swatch_id) {
+                true => adjust(rgb, adjustments(), adjustment_space()),
+                false => rgb,
+            };
+            rgb.as_css_variables()
+        };
+
+        let on_drag_start = move |ev: DragEvent| {
+            dragged.set(Some((palette_id, swatch_id)));
+            if let Some(data) = ev.data_transfer() {
+                // Firefox doesn't start the drag without any data.
+                let _ = data
+                    .set_data("text/plain", &format!("#{}", rgb.as_hex_code()));
+            }
+        };
+        let on_drag_over = move |ev: DragEvent| {
+            if dragged().is_some_and(|(id, _)| id == palette_id) {
+                ev.prevent_default();
+            }
+        };
+        let on_drop = move |ev: DragEvent| {
+            let Some((_, dragged_id)) = dragged() else {
+                return;
+            };
+            ev.prevent_default();
+            update_palette(palette_id, &|palette| {
+                let Some(index) = palette
+                    .swatches
+                    .iter()
+                    .position(|swatch| swatch.id == swatch_id)
+                else {
+                    return;
+                };
+                palette.move_swatch(dragged_id, index);
+            });
+            dragged.set(None);
+        };
+
+        view! {
+            <li
+                class="swatch"
+                class:dragged=move || dragged().is_some_and(|(_, id)| id == swatch_id)
+                class:selected=move || is_selected(swatch_id)
+                draggable="true"
+                on:dragstart=on_drag_start
+                on:dragover=on_drag_over
+                on:drop=on_drop
+                on:dragend=move |_| dragged.set(None)
+            >
+                <button
+                    class="color"
+                    style=style
+                    title=format!("Load {}", label)
+                    aria-label=format!("Load {}", label)
+                    on:click=move |_| {
+                        color.set(
+                            DynamicColor::from_color(rgb)
+                                .set_color_space(color.get_untracked().color_space()),
+                        )
+                    }
+                />
+                <button
+                    class="select"
+                    title=format!("Select {} for adjusting", label)
+                    aria-label=format!("Select {} for adjusting", label)
+                    aria-pressed=move || is_selected(swatch_id).to_string()
+                    on:click=move |_| toggle_selected(swatch_id)
+                >
+                    <Icon icon_id="check"/>
+                </button>
+                <button
+                    class="remove"
+                    title=format!("Remove {}", label)
+                    aria-label=format!("Remove {}", label)
+                    on:click=move |_| {
+                        update_palette(palette_id, &|palette| palette.remove_swatch(swatch_id))
+                    }
+                >
+                    <Icon icon_id="x"/>
+                </button>
+            </li>
+        }
+    };
+
+    let palette_view = move |palette_id: u64| {
+        let name = move || {
+            library.with(|library| {
+                library
+                    .palette(palette_id)
+                    .map(|palette| palette.name.clone())
+                    .unwrap_or_default()
+            })
+        };
+        let swatches = move || {
+            library.with(|library| {
+                library
+                    .palette(palette_id)
+                    .map(|palette| palette.swatches.clone())
+                    .unwrap_or_default()
+            })
+        };
+
+        let on_rename = move |ev: Event| {
+            let new_name = event_target_value(&ev);
+            update_palette(palette_id, &|palette| {
+                palette.name.clone_from(&new_name)
+            });
+        };
+        // Selects all swatches of the palette, or none if all are selected.
+        let select_all = move |_| {
+            let ids = swatches()
+                .into_iter()
+                .map(|swatch| swatch.id)
+                .collect::<Vec<_>>();
+            selected.update(|selected| {
+                if ids.iter().all(|id| selected.contains(id)) {
+                    ids.iter().for_each(|id| {
+                        selected.remove(id);
+                    });
+                } else {
+                    selected.extend(ids);
+                }
+            });
+        };
+        let add_current_color = move |_| {
+            let rgb = color.get_untracked().to_color::<Rgb>();
+            update_palette(palette_id, &|palette| palette.add_color(rgb));
+        };
+        let export_palette = move |_| {
+            let format = export_format.get_untracked();
+            library.with_untracked(|library| {
+                let Some(palette) = library.palette(palette_id) else {
+                    return;
+                };
+                let name = match palette.name.trim() {
+                    "" => "palette",
+                    name => name,
+                };
+                match format.write(palette) {
+                    Ok(bytes) => download_file(
+                        &format!("{}.{}", name, format.extension()),
+                        format.mime_type(),
+                        &bytes,
+                    ),
+                    Err(err) => toasts::add_toast(format!(
+                        "Couldn't export '{}': {}.",
+                        name, err
+                    )),
+                }
+            });
+        };
+        let delete_palette = move |_| {
+            let confirmed = window()
+                .confirm_with_message(&format!(
+                    "Delete the palette '{}'?",
+                    name()
+                ))
+                .unwrap_or(false);
+            if confirmed {
+                library.update(|library| library.remove_palette(palette_id));
+            }
+        };
+
+        view! {
+            <li class="palette">
+                <div class="header">
+                    <input
+                        type="text"
+                        aria-label="Palette name"
+                        prop:value=name
+                        on:change=on_rename
+                    />
+                    <button class="add-color" on:click=add_current_color>
+                        <Icon icon_id="plus"/>
+                        "Add Current Color"
+                    </button>
+                    <button
+                        class="select-all"
+                        title="Select swatches for adjusting"
+                        aria-label="Select swatches for adjusting"
+                        on:click=select_all
+                    >
+                        <Icon icon_id="check-square"/>
+                    </button>
+                    <button
+                        class="export"
+                        title="Export palette"
+                        aria-label="Export palette"
+                        on:click=export_palette
+                    >
+                        <Icon icon_id="download"/>
+                    </button>
+                    <button
+                        class="delete"
+                        title="Delete palette"
+                        aria-label="Delete palette"
+                        on:click=delete_palette
+                    >
+                        <Icon icon_id="trash-2"/>
+                    </button>
+                </div>
+                <ul class="swatches">
+                    // Swatches are rendered again when their color is adjusted.
+                    <For
+                        each=swatches
+                        key=|swatch| (swatch.id, swatch.color.as_hex_code())
+                        children=move |swatch| swatch_view(palette_id, swatch)
+                    />
+                </ul>
+            </li>
+        }
+    };
+
+    view! {
+        <section
+            class="tool-panel palettes"
+            on:dragover=on_files_drag_over
+            on:drop=on_files_drop
         >
-            <div class="hue-slider__cursor"/>
-        </div>
+            <h2>"Palettes"</h2>
+            <ul class="palette-list">
+                <For
+                    each=move || {
+                        library.with(|library| {
+                            library.palettes.iter().map(|palette| palette.id).collect::<Vec<_>>()
+                        })
+                    }
+                    key=|id| *id
+                    children=palette_view
+                />
+            </ul>
+            <Show when=move || { selected_count() > 0 }>
+                <div class="adjustments">
+                    <h3>
+                        {move || match selected_count() {
+                            1 => "Adjust 1 Swatch".to_owned(),
+                            count => format!("Adjust {} Swatches", count),
+                        }}
+                    </h3>
+                    <StoredRadioGroup
+                        options=adjustment_space_options.clone()
+                        title="Color Space".to_owned()
+                        name=Signal::derive(|| "palette-adjustment-space".to_owned())
+                        on_change=set_adjustment_space
+                        key="s_palette_adjustment_space"
+                    />
+                    <NumberInput
+                        value=Signal::derive(move || adjustments().hue_rotation)
+                        set_value=move |degrees| {
+                            adjustments.update(|adjustments| adjustments.hue_rotation = degrees)
+                        }
+                        min=-180.
+                        max=180.
+                        step=1.
+                        full_turn=Some(360.)
+                        decimals=0
+                        prefix=Some("Hue".to_owned())
+                        postfix=Some("°".to_owned())
+                        placeholder="0"
+                    />
+                    <NumberInput
+                        value=Signal::derive(move || adjustments().chroma_scale)
+                        set_value=move |scale| {
+                            adjustments.update(|adjustments| adjustments.chroma_scale = scale)
+                        }
+                        min=0.
+                        max=4.
+                        step=0.05
+                        decimals=2
+                        prefix=Some("Chroma".to_owned())
+                        postfix=Some("×".to_owned())
+                        placeholder="1"
+                    />
+                    <NumberInput
+                        value=Signal::derive(move || adjustments().lightness_shift * 100.)
+                        set_value=move |percent| {
+                            adjustments.update(|adjustments| adjustments.lightness_shift = percent / 100.)
+                        }
+                        min=-100.
+                        max=100.
+                        step=1.
+                        decimals=0
+                        prefix=Some("Lightness".to_owned())
+                        postfix=Some("%".to_owned())
+                        placeholder="0"
+                    />
+                    <label class="invert">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || adjustments().invert
+                            on:change=move |ev| {
+                                let invert = event_target_checked(&ev);
+                                adjustments.update(|adjustments| adjustments.invert = invert)
+                            }
+                        />
+                        "Invert"
+                    </label>
+                    <div class="actions">
+                        <button
+                            class="apply"
+                            disabled=move || adjustments().is_identity()
+                            on:click=apply_adjustments
+                        >
+                            <Icon icon_id="check"/>
+                            "Apply"
+                        </button>
+                        <button class="clear" on:click=clear_selection>
+                            <Icon icon_id="x"/>
+                            "Clear Selection"
+                        </button>
+                    </div>
+                </div>
+            </Show>
+            <button class="add-palette" on:click=add_palette>
+                <Icon icon_id="folder-plus"/>
+                "New Palette"
+            </button>
+            <label class="file-input">
+                "Import"
+                <input
+                    type="file"
+                    accept=".gpl,.ase,.aco,.txt,.css,.json"
+                    multiple
+                    on:change=on_file_change
+                />
+            </label>
+            <p class="info">"Palette files can also be dropped here."</p>
+            {move || {
+                import_error().map(|reason| view! { <p class="error">{reason}</p> })
+            }}
+            <StoredRadioGroup
+                options=format_options
+                title="Export Format".to_owned()
+                name=Signal::derive(|| "palette-export-format".to_owned())
+                on_change=set_export_format
+                key="s_palette_export_format"
+            />
+        </section>
+    }
+}
+
+/// How many pixels are shown across the eyedropper loupe.
+const LOUPE_PIXELS: i64 = 15;
+/// How large every pixel is drawn in the loupe, in canvas pixels.
+const LOUPE_SCALE: f64 = 8.;
+
+/// How many dominant colors are extracted from images by default.
+const DEFAULT_DOMINANT_COLORS: usize = 6;
+const MAX_DOMINANT_COLORS: usize = 16;
+
+/// Lets the user load an image by uploading, dropping or pasting it, and pick
+/// colors from it.
+#[component]
+pub fn ImageEyedropper(
+    color: RwSignal<DynamicColor>,
+    /// Where the dominant colors of the image are saved as a palette. The
+    /// option to save them is only shown if it's given.
+    #[prop(optional)]
+    library: Option<RwSignal<PaletteLibrary>>,
+    /// The loaded image, which can be shared with other panels that use it.
+    #[prop(optional)]
+    image: Option<RwSignal<Option<RgbaImage>>>,
+) -> impl IntoView {
+    let sample_size_options = [1, 3, 5, 9]
+        .into_iter()
+        .map(|size: u32| (format!("{0}×{0}", size), size))
+        .collect::<Vec<_>>();
+    let (sample_size, set_sample_size) = create_signal(1u32);
+
+    let image = image.unwrap_or_else(|| create_rw_signal(None));
+    let (image_name, set_image_name) = create_signal(String::new());
+    let (load_error, set_load_error) = create_signal::<Option<String>>(None);
+    // The pixel under the pointer.
+    let hovered = create_rw_signal::<Option<(i64, i64)>>(None);
+
+    let canvas_ref = create_node_ref::<Canvas>();
+    let loupe_ref = create_node_ref::<Canvas>();
+
+    let load_file = move |file: web_sys::File| {
+        if !file.type_().starts_with("image/") {
+            set_load_error(Some(format!("'{}' isn't an image.", file.name())));
+            return;
+        }
+        set_load_error(None);
+        let name = file.name();
+        read_image_file(&file, move |pixels| {
+            let Some(rgba_image) = pixels.and_then(|(width, height, data)| {
+                RgbaImage::new(width, height, data)
+            }) else {
+                set_load_error(Some(format!(
+                    "'{}' couldn't be decoded.",
+                    name
+                )));
+                return;
+            };
+            set_image_name(
+                name.rsplit_once('.')
+                    .map_or(name.as_str(), |(name, _)| name)
+                    .to_owned(),
+            );
+            hovered.set(None);
+            image.set(Some(rgba_image));
+        });
+    };
+
+    let on_file_change = move |ev: Event| {
+        let file_input = event_target::<web_sys::HtmlInputElement>(&ev);
+        if let Some(file) = file_input.files().and_then(|files| files.get(0)) {
+            load_file(file);
+        }
+    };
+    let on_drop = move |ev: DragEvent| {
+        let Some(file) = ev
+            .data_transfer()
+            .and_then(|data| data.files())
+            .and_then(|files| files.get(0))
+        else {
+            return;
+        };
+        ev.prevent_default();
+        load_file(file);
+    };
+    // Pasting into a text field, like the hex code input, shouldn't replace
+    // the image.
+    create_managed_window_event_listener("paste", move |ev| {
+        if is_text_field_event(ev) {
+            return;
+        }
+        let Some(file) = ev
+            .dyn_ref::<web_sys::ClipboardEvent>()
+            .and_then(|ev| ev.clipboard_data())
+            .and_then(|data| data.files())
+            .and_then(|files| files.get(0))
+        else {
+            return;
+        };
+        if file.type_().starts_with("image/") {
+            ev.prevent_default();
+            load_file(file);
+        }
+    });
+
+    create_effect(move |_| {
+        let Some(canvas) = canvas_ref.get() else {
+            return;
+        };
+        image.with(|image| {
+            if let Some(image) = image {
+                draw_rgba_image(
+                    &canvas,
+                    image.width(),
+                    image.height(),
+                    image.data(),
+                );
+            }
+        });
+    });
+
+    let pixel_at = move |ev: &MouseEvent| -> Option<(i64, i64)> {
+        let canvas = canvas_ref.get_untracked()?;
+        let rect = canvas.get_bounding_client_rect();
+        let (width, height) = image.with_untracked(|image| {
+            image.as_ref().map(|image| (image.width(), image.height()))
+        })?;
+
+        let x = (ev.client_x() as f64 - rect.left()) / rect.width();
+        let y = (ev.client_y() as f64 - rect.top()) / rect.height();
+        if !(0. ..1.).contains(&x) || !(0. ..1.).contains(&y) {
+            return None;
+        }
+        Some((
+            (x * width as f64).floor() as i64,
+            (y * height as f64).floor() as i64,
+        ))
+    };
+
+    let sampled_color = move || {
+        let (x, y) = hovered()?;
+        image.with(|image| image.as_ref()?.average(x, y, sample_size()))
+    };
+
+    let on_canvas_click = move |ev: MouseEvent| {
+        let Some((x, y)) = pixel_at(&ev) else {
+            return;
+        };
+        let rgb = image.with_untracked(|image| {
+            image.as_ref()?.average(x, y, sample_size.get_untracked())
+        });
+        if let Some(rgb) = rgb {
+            color.set(
+                DynamicColor::from_color(rgb)
+                    .set_color_space(color.get_untracked().color_space()),
+            );
+        }
+    };
+
+    // Draw the pixels around the hovered one enlarged, with a grid and the
+    // sampled area outlined.
+    create_effect(move |_| {
+        let Some(loupe) = loupe_ref.get() else {
+            return;
+        };
+        let Some(context) = canvas_context_2d(&loupe) else {
+            return;
+        };
+        let size = LOUPE_PIXELS as f64 * LOUPE_SCALE;
+        loupe.set_width(size as u32);
+        loupe.set_height(size as u32);
+        context.clear_rect(0., 0., size, size);
+
+        let Some((x, y)) = hovered() else {
+            return;
+        };
+        let radius = LOUPE_PIXELS / 2;
+        image.with(|image| {
+            let Some(image) = image else {
+                return;
+            };
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    let Some([r, g, b, a]) = image.rgba(x + dx, y + dy) else {
+                        continue;
+                    };
+                    context.set_fill_style(&JsValue::from_str(&format!(
+                        "rgba({}, {}, {}, {})",
+                        r,
+                        g,
+                        b,
+                        a as f64 / 255.
+                    )));
+                    context.fill_rect(
+                        (dx + radius) as f64 * LOUPE_SCALE,
+                        (dy + radius) as f64 * LOUPE_SCALE,
+                        LOUPE_SCALE,
+                        LOUPE_SCALE,
+                    );
+                }
+            }
+        });
+
+        context.set_line_width(1.);
+        context
+            .set_stroke_style(&JsValue::from_str("rgba(128, 128, 128, 0.4)"));
+        context.begin_path();
+        for index in 1..LOUPE_PIXELS {
+            let offset = index as f64 * LOUPE_SCALE + 0.5;
+            context.move_to(offset, 0.);
+            context.line_to(offset, size);
+            context.move_to(0., offset);
+            context.line_to(size, offset);
+        }
+        context.stroke();
+
+        let sample_size = sample_size() as f64;
+        let sample_start =
+            (radius as f64 - (sample_size / 2.).floor()) * LOUPE_SCALE;
+        context.set_line_width(2.);
+        context.set_stroke_style(&JsValue::from_str("white"));
+        context.stroke_rect(
+            sample_start,
+            sample_start,
+            sample_size * LOUPE_SCALE,
+            sample_size * LOUPE_SCALE,
+        );
+    });
+
+    let method_options = QuantizeMethod::ALL
+        .into_iter()
+        .map(|method| (method.to_ui_string(()), method))
+        .collect::<Vec<_>>();
+    let (method, set_method) = create_signal(QuantizeMethod::MedianCut);
+    let (dominant_count, set_dominant_count) =
+        create_signal(DEFAULT_DOMINANT_COLORS);
+
+    let dominant = create_memo(move |_| {
+        image.with(|image| {
+            image.as_ref().map_or(vec![], |image| {
+                dominant_colors(image, dominant_count(), method())
+            })
+        })
+    });
+
+    let on_dominant_count_input = move |ev: Event| {
+        if let Ok(value) = event_target_value(&ev).parse::<usize>() {
+            set_dominant_count(value.clamp(1, MAX_DOMINANT_COLORS));
+        }
+    };
+
+    let dominant_color_view = move |dominant_color: DominantColor| {
+        let rgb = dominant_color.color;
+        let hex_code = format!("#{}", rgb.as_hex_code());
+        view! {
+            <li>
+                <button
+                    class="color"
+                    style=rgb.as_css_variables()
+                    title=format!("Load {}", hex_code)
+                    aria-label=format!("Load {}", hex_code)
+                    on:click=move |_| {
+                        color.set(
+                            DynamicColor::from_color(rgb)
+                                .set_color_space(color.get_untracked().color_space()),
+                        )
+                    }
+                />
+                <span class="proportion">
+                    {format!(
+                        "{}%",
+                        naturally_format_float(dominant_color.proportion * 100., 0, 1),
+                    )}
+                </span>
+            </li>
+        }
+    };
+
+    let save_palette = move |_| {
+        let Some(library) = library else {
+            return;
+        };
+        let mut palette = Palette::new(format!("{} colors", image_name()));
+        for dominant_color in dominant.get_untracked() {
+            palette.add_color(dominant_color.color);
+        }
+        library.update(|library| library.palettes.push(palette));
+    };
+
+    let sample_info = move || {
+        let (x, y) = hovered()?;
+        let rgb = sampled_color()?;
+        Some(format!("#{} at ({}, {})", rgb.as_hex_code(), x, y))
+    };
+
+    view! {
+        <section
+            class="tool-panel image-eyedropper"
+            on:dragover=move |ev: DragEvent| ev.prevent_default()
+            on:drop=on_drop
+        >
+            <h2>"Image Eyedropper"</h2>
+            <label class="file-input">
+                "Image"
+                <input type="file" accept="image/*" on:change=on_file_change/>
+            </label>
+            <p class="info">"Images can also be dropped or pasted here."</p>
+            {move || {
+                load_error().map(|reason| view! { <p class="error">{reason}</p> })
+            }}
+            <StoredRadioGroup
+                options=sample_size_options
+                title="Sample Size".to_owned()
+                name=Signal::derive(|| "eyedropper-sample-size".to_owned())
+                on_change=set_sample_size
+                key="s_eyedropper_sample_size"
+            />
+            <div class="viewer" class:empty=move || image.with(Option::is_none)>
+                <canvas
+                    class="image"
+                    _ref=canvas_ref
+                    on:pointermove=move |ev| hovered.set(pixel_at(&ev))
+                    on:pointerleave=move |_| hovered.set(None)
+                    on:click=on_canvas_click
+                />
+                <div class="sample">
+                    <canvas class="loupe" _ref=loupe_ref/>
+                    <p class="info">{sample_info}</p>
+                </div>
+            </div>
+            <div class="dominant-colors" class:empty=move || image.with(Option::is_none)>
+                <h3>"Dominant Colors"</h3>
+                <StoredRadioGroup
+                    options=method_options
+                    title="Method".to_owned()
+                    name=Signal::derive(|| "dominant-colors-method".to_owned())
+                    on_change=set_method
+                    key="s_dominant_colors_method"
+                />
+                <label>
+                    "Colors"
+                    <input
+                        type="number"
+                        min="1"
+                        max=MAX_DOMINANT_COLORS
+                        prop:value=dominant_count
+                        on:input=on_dominant_count_input
+                    />
+                </label>
+                <ul class="swatches">
+                    {move || dominant().into_iter().map(dominant_color_view).collect_view()}
+                </ul>
+                {library
+                    .map(|_| {
+                        view! {
+                            <button class="save" on:click=save_palette>
+                                <Icon icon_id="folder-plus"/>
+                                "Save as Palette"
+                            </button>
+                        }
+                    })}
+            </div>
+        </section>
+    }
+}
+
+/// Reduces the image loaded in the eyedropper to the colors of a palette.
+#[component]
+pub fn ImageRecolor(
+    #[prop(into)] image: Signal<Option<RgbaImage>>,
+    #[prop(into)] library: Signal<PaletteLibrary>,
+) -> impl IntoView {
+    /// The largest number of pixels the preview is recolored at.
+    const MAX_PREVIEW_PIXELS: u32 = 512 * 512;
+
+    let metric_options = DistanceMetric::ALL
+        .into_iter()
+        .map(|metric| (metric.to_ui_string(()), metric))
+        .collect::<Vec<_>>();
+    let dithering_options = Dithering::ALL
+        .into_iter()
+        .map(|dithering| (dithering.to_ui_string(()), dithering))
+        .collect::<Vec<_>>();
+    let (metric, set_metric) = create_signal(DistanceMetric::Oklab);
+    let (dithering, set_dithering) = create_signal(Dithering::None);
+
+    let (selected_palette, set_selected_palette) =
+        create_signal::<Option<u64>>(None);
+    // Fall back to the first palette if the selected one doesn't exist.
+    let palette = create_memo(move |_| {
+        library.with(|library| {
+            selected_palette()
+                .and_then(|id| library.palette(id))
+                .or(library.palettes.first())
+                .cloned()
+        })
+    });
+
+    // Only the colors matter, so renaming palettes doesn't recolor the image.
+    let colors = create_memo(move |_| {
+        palette.with(|palette| {
+            palette.as_ref().map_or(vec![], |palette| {
+                palette.swatches.iter().map(|swatch| swatch.color).collect()
+            })
+        })
+    });
+    // Large images are previewed at a lower resolution to stay responsive,
+    // and recolored at full resolution when downloaded.
+    let preview = create_memo(move |_| {
+        image.with(|image| {
+            image
+                .as_ref()
+                .map(|image| image.downscale(MAX_PREVIEW_PIXELS))
+        })
+    });
+    let recolored = create_memo(move |_| {
+        preview.with(|preview| {
+            colors.with(|colors| {
+                recolor(preview.as_ref()?, colors, metric(), dithering())
+            })
+        })
+    });
+
+    let canvas_ref = create_node_ref::<Canvas>();
+    create_effect(move |_| {
+        let Some(canvas) = canvas_ref.get() else {
+            return;
+        };
+        recolored.with(|recolored| {
+            if let Some(recolored) = recolored {
+                draw_rgba_image(
+                    &canvas,
+                    recolored.width(),
+                    recolored.height(),
+                    recolored.data(),
+                );
+            }
+        });
+    });
+
+    let download_png = move |_| {
+        let recolored = image.with_untracked(|image| {
+            colors.with_untracked(|colors| {
+                recolor(
+                    image.as_ref()?,
+                    colors,
+                    metric.get_untracked(),
+                    dithering.get_untracked(),
+                )
+            })
+        });
+        let url: Option<String> = try {
+            let recolored = recolored?;
+            let canvas = document()
+                .create_element("canvas")
+                .ok()?
+                .dyn_into::<web_sys::HtmlCanvasElement>()
+                .ok()?;
+            draw_rgba_image(
+                &canvas,
+                recolored.width(),
+                recolored.height(),
+                recolored.data(),
+            )?;
+            canvas.to_data_url_with_type("image/png").ok()?
+        };
+        let Some(url) = url else {
+            error!("Failed to encode the recolored image.");
+            return;
+        };
+        let name = palette
+            .with_untracked(|palette| {
+                palette.as_ref().map(|palette| palette.name.clone())
+            })
+            .unwrap_or_default();
+        download_url(&format!("recolored {}.png", name.trim()), &url);
+    };
+
+    let palette_options = move || {
+        let selected_id =
+            palette.with(|palette| palette.as_ref().map(|palette| palette.id));
+        library.with(|library| {
+            library
+                .palettes
+                .iter()
+                .map(|palette| {
+                    view! {
+                        <option
+                            value=palette.id.to_string()
+                            selected=selected_id == Some(palette.id)
+                        >
+                            {palette.name.clone()}
+                        </option>
+                    }
+                })
+                .collect_view()
+        })
+    };
+
+    let status = move || {
+        if image.with(Option::is_none) {
+            Some("Load an image in the eyedropper to recolor it.")
+        } else if palette.with(|palette| {
+            palette
+                .as_ref()
+                .is_none_or(|palette| palette.swatches.is_empty())
+        }) {
+            Some("Choose a palette with at least one color.")
+        } else {
+            None
+        }
+    };
+
+    view! {
+        <section class="tool-panel image-recolor">
+            <h2>"Recolor Image"</h2>
+            <label class="palette">
+                "Palette"
+                <div class="fancy-select">
+                    <select on:change=move |ev| {
+                        set_selected_palette(
+                            event_target_value(&ev).parse().ok(),
+                        )
+                    }>{palette_options}</select>
+                    <Icon icon_id="chevron-down"/>
+                </div>
+            </label>
+            <StoredRadioGroup
+                options=metric_options
+                title="Distance".to_owned()
+                name=Signal::derive(|| "recolor-metric".to_owned())
+                on_change=set_metric
+                key="s_recolor_metric"
+            />
+            <StoredRadioGroup
+                options=dithering_options
+                title="Dithering".to_owned()
+                name=Signal::derive(|| "recolor-dithering".to_owned())
+                on_change=set_dithering
+                key="s_recolor_dithering"
+            />
+            {move || status().map(|status| view! { <p class="info">{status}</p> })}
+            <div class="result" class:empty=move || recolored.with(Option::is_none)>
+                <canvas class="image" _ref=canvas_ref/>
+                <button class="download" on:click=download_png>
+                    <Icon icon_id="download"/>
+                    "Download PNG"
+                </button>
+            </div>
+        </section>
     }
 }
@@ -0,0 +1,50 @@
+//! Luminance and contrast as defined by WCAG.
+//!
+//! Source: https://www.w3.org/TR/WCAG21/#dfn-relative-luminance
+
+use super::*;
+
+/// The relative luminance of a color, in the range 0 for black to 1 for
+/// white.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+/// use approx::assert_relative_eq;
+///
+/// let gray = Rgb::from_components((128., 128., 128.));
+///
+/// assert_relative_eq!(relative_luminance(gray), 0.2159, epsilon = 1e-4);
+/// ```
+pub fn relative_luminance(rgb: Rgb) -> f64 {
+    let (r, g, b) = rgb.as_floats();
+
+    0.2126 * srgb_to_linear(r)
+        + 0.7152 * srgb_to_linear(g)
+        + 0.0722 * srgb_to_linear(b)
+}
+
+/// The contrast ratio between two colors, in the range 1 for the same
+/// luminance to 21 for black on white. The order of the colors doesn't
+/// matter.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+/// use approx::assert_relative_eq;
+///
+/// let black = Rgb::from_components((0., 0., 0.));
+/// let white = Rgb::from_components((255., 255., 255.));
+/// let gray = Rgb::from_components((118., 118., 118.));
+///
+/// assert_relative_eq!(contrast_ratio(black, white), 21.);
+/// assert_relative_eq!(contrast_ratio(white, black), 21.);
+/// assert_relative_eq!(contrast_ratio(gray, white), 4.54, epsilon = 0.01);
+/// assert_relative_eq!(contrast_ratio(gray, gray), 1.);
+/// ```
+pub fn contrast_ratio(a: Rgb, b: Rgb) -> f64 {
+    let a = relative_luminance(a);
+    let b = relative_luminance(b);
+
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
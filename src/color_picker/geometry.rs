@@ -0,0 +1,337 @@
+//! The shapes the two dimensional control of the color picker can take.
+//!
+//! Points are relative to the square the picker is drawn in, where `(0, 0)`
+//! is the top left corner and `(1, 1)` the bottom right one. Hues are in
+//! turns, so that they're in the range 0 to 1 like the other floats, and go
+//! clockwise from the top like CSS `conic-gradient()`.
+
+use super::*;
+use std::f64::consts::{SQRT_2, TAU};
+
+/// The outer radius of the hue ring.
+const RING_OUTER_RADIUS: f64 = 0.5;
+/// The inner radius of the hue ring.
+const RING_INNER_RADIUS: f64 = 0.41;
+/// The radius of the circle the inner square or triangle fits in, leaving
+/// some space to the ring.
+const INNER_RADIUS: f64 = 0.38;
+
+/// The highest chroma shown by the OKLCh plane, in percent like [Oklch]. It's
+/// a bit more than the highest chroma in sRGB, which is reached by blue.
+pub const OKLCH_PLANE_CHROMA_MAX: f64 = 85.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PickerGeometry {
+    /// A saturation/value square, with a separate hue slider.
+    Square,
+    /// A hue ring around a saturation/value square.
+    Wheel,
+    /// A hue ring around a triangle between the pure hue, white and black.
+    Triangle,
+    /// A lightness/chroma plane of a single OKLCh hue, with a separate hue
+    /// slider. Colors outside of the sRGB gamut are dimmed.
+    OklchPlane,
+}
+
+/// A part of a picker which can be dragged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PickerPart {
+    HueRing,
+    /// The square, triangle or plane.
+    Inner,
+}
+
+impl PickerGeometry {
+    pub const ALL: [PickerGeometry; 4] = [
+        PickerGeometry::Square,
+        PickerGeometry::Wheel,
+        PickerGeometry::Triangle,
+        PickerGeometry::OklchPlane,
+    ];
+
+    /// If the geometry includes a hue ring, instead of using a hue slider.
+    pub fn has_hue_ring(&self) -> bool {
+        matches!(self, PickerGeometry::Wheel | PickerGeometry::Triangle)
+    }
+
+    /// The part of the picker at a point, or `None` if the point is outside
+    /// of the picker.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// let wheel = PickerGeometry::Wheel;
+    ///
+    /// assert_eq!(wheel.part_at(0.5, 0.5), Some(PickerPart::Inner));
+    /// assert_eq!(wheel.part_at(0.5, 0.05), Some(PickerPart::HueRing));
+    /// assert_eq!(wheel.part_at(0., 0.), None);
+    /// ```
+    pub fn part_at(&self, x: f64, y: f64) -> Option<PickerPart> {
+        if !self.has_hue_ring() {
+            return Some(PickerPart::Inner);
+        }
+
+        let distance = (x - 0.5).hypot(y - 0.5);
+        if distance > RING_OUTER_RADIUS {
+            None
+        } else if distance >= RING_INNER_RADIUS {
+            Some(PickerPart::HueRing)
+        } else {
+            Some(PickerPart::Inner)
+        }
+    }
+
+    /// The HSV saturation and value at a point on the inner part, clamped to
+    /// the shape of it. The triangle is rotated towards the hue.
+    ///
+    /// Should only be used for the HSV geometries, which excludes
+    /// [PickerGeometry::OklchPlane].
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    /// use approx::assert_relative_eq;
+    ///
+    /// for geometry in [
+    ///     PickerGeometry::Square,
+    ///     PickerGeometry::Wheel,
+    ///     PickerGeometry::Triangle,
+    /// ] {
+    ///     let (x, y) = geometry.sat_value_position(0.3, 0.25, 0.75);
+    ///     let (sat, value) = geometry.sat_value_at(0.3, x, y);
+    ///
+    ///     assert_relative_eq!(sat, 0.25, epsilon = 1e-9);
+    ///     assert_relative_eq!(value, 0.75, epsilon = 1e-9);
+    /// }
+    /// ```
+    pub fn sat_value_at(&self, hue: f64, x: f64, y: f64) -> (f64, f64) {
+        match self {
+            PickerGeometry::Square | PickerGeometry::OklchPlane => {
+                (x.clamp(0., 1.), (1. - y).clamp(0., 1.))
+            }
+            PickerGeometry::Wheel => {
+                let (left, size) = wheel_square_bounds();
+                (
+                    ((x - left) / size).clamp(0., 1.),
+                    (1. - (y - left) / size).clamp(0., 1.),
+                )
+            }
+            PickerGeometry::Triangle => {
+                let [hue_weight, white_weight, _] =
+                    clamp_barycentric(triangle_barycentric(hue, x, y));
+                let value = hue_weight + white_weight;
+                let sat = if value > 0. { hue_weight / value } else { 0. };
+                (sat.clamp(0., 1.), value.clamp(0., 1.))
+            }
+        }
+    }
+
+    /// The point on the inner part with the HSV saturation and value, the
+    /// inverse of [Self::sat_value_at].
+    pub fn sat_value_position(
+        &self,
+        hue: f64,
+        sat: f64,
+        value: f64,
+    ) -> (f64, f64) {
+        match self {
+            PickerGeometry::Square | PickerGeometry::OklchPlane => {
+                (sat, 1. - value)
+            }
+            PickerGeometry::Wheel => {
+                let (left, size) = wheel_square_bounds();
+                (left + sat * size, left + (1. - value) * size)
+            }
+            PickerGeometry::Triangle => {
+                let weights = [sat * value, (1. - sat) * value, 1. - value];
+                let vertices = triangle_vertices(hue);
+                let axis = |axis: usize| {
+                    weights
+                        .iter()
+                        .zip(vertices)
+                        .map(|(weight, vertex)| weight * vertex[axis])
+                        .sum::<f64>()
+                };
+                (axis(0), axis(1))
+            }
+        }
+    }
+
+    /// Draw the picker as a `size` by `size` image. `hue` is the HSV hue for
+    /// the inner part of the HSV geometries, and the OKLCh hue for
+    /// [PickerGeometry::OklchPlane].
+    pub fn render(&self, hue: f64, size: u32) -> RgbaImage {
+        let mut data = Vec::with_capacity(size as usize * size as usize * 4);
+        // Edges are faded over about one pixel, to smooth them.
+        let pixel = 1. / size as f64;
+
+        for row in 0..size {
+            for column in 0..size {
+                let x = (column as f64 + 0.5) * pixel;
+                let y = (row as f64 + 0.5) * pixel;
+                let (rgb, alpha) = self.render_pixel(hue, x, y, pixel);
+                let (r, g, b) = rgb.as_bytes();
+                data.extend([
+                    r,
+                    g,
+                    b,
+                    (alpha.clamp(0., 1.) * 255.).round() as u8,
+                ]);
+            }
+        }
+
+        RgbaImage::new(size, size, data)
+            .expect("the image data should contain every pixel")
+    }
+
+    fn render_pixel(&self, hue: f64, x: f64, y: f64, pixel: f64) -> (Rgb, f64) {
+        let transparent = (Rgb::from_components((0., 0., 0.)), 0.);
+        let hsv = |hue: f64, sat: f64, value: f64| {
+            Hsv::from_floats((hue, sat, value)).as_rgb()
+        };
+
+        match self {
+            PickerGeometry::Square => (hsv(hue, x, 1. - y), 1.),
+            PickerGeometry::OklchPlane => {
+                let (lightness, chroma) = oklch_plane_at(x, y);
+                let oklch = Oklch {
+                    l: lightness,
+                    c: chroma,
+                    h: hue * 360.,
+                };
+                let in_gamut =
+                    linear_srgb_in_gamut(oklch.as_oklab().as_linear_srgb());
+                (oklch.as_rgb(), if in_gamut { 1. } else { 0.25 })
+            }
+            PickerGeometry::Wheel | PickerGeometry::Triangle => {
+                let distance = (x - 0.5).hypot(y - 0.5);
+                if distance >= RING_INNER_RADIUS - pixel {
+                    let coverage = ((RING_OUTER_RADIUS - distance) / pixel)
+                        .min((distance - RING_INNER_RADIUS) / pixel + 1.);
+                    return (hsv(hue_at(x, y), 1., 1.), coverage);
+                }
+
+                if *self == PickerGeometry::Wheel {
+                    let (left, size) = wheel_square_bounds();
+                    let inside = (left..=left + size).contains(&x)
+                        && (left..=left + size).contains(&y);
+                    if !inside {
+                        return transparent;
+                    }
+                } else if triangle_barycentric(hue, x, y)
+                    .iter()
+                    .any(|weight| *weight < 0.)
+                {
+                    return transparent;
+                }
+
+                let (sat, value) = self.sat_value_at(hue, x, y);
+                (hsv(hue, sat, value), 1.)
+            }
+        }
+    }
+}
+
+impl UiDisplay for PickerGeometry {
+    type Environment = ();
+
+    fn to_ui_string(&self, _env: Self::Environment) -> String {
+        match self {
+            PickerGeometry::Square => "Square",
+            PickerGeometry::Wheel => "Wheel",
+            PickerGeometry::Triangle => "Triangle",
+            PickerGeometry::OklchPlane => "OKLCh Plane",
+        }
+        .to_owned()
+    }
+}
+
+impl Display for PickerGeometry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PickerGeometry::Square => "Square",
+            PickerGeometry::Wheel => "Wheel",
+            PickerGeometry::Triangle => "Triangle",
+            PickerGeometry::OklchPlane => "OklchPlane",
+        })
+    }
+}
+
+impl FromStr for PickerGeometry {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Square" => Ok(PickerGeometry::Square),
+            "Wheel" => Ok(PickerGeometry::Wheel),
+            "Triangle" => Ok(PickerGeometry::Triangle),
+            "OklchPlane" => Ok(PickerGeometry::OklchPlane),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The hue at a point on the hue ring.
+pub fn hue_at(x: f64, y: f64) -> f64 {
+    ((x - 0.5).atan2(0.5 - y) / TAU).rem_euclid(1.)
+}
+
+/// The point in the middle of the hue ring with the hue.
+pub fn hue_position(hue: f64) -> (f64, f64) {
+    point_on_circle(hue, (RING_OUTER_RADIUS + RING_INNER_RADIUS) / 2.)
+}
+
+/// The OKLCh lightness and chroma at a point on the OKLCh plane, in percent
+/// like [Oklch]. The chroma increases to the right and the lightness upwards.
+pub fn oklch_plane_at(x: f64, y: f64) -> (f64, f64) {
+    (
+        (1. - y).clamp(0., 1.) * 100.,
+        x.clamp(0., 1.) * OKLCH_PLANE_CHROMA_MAX,
+    )
+}
+
+/// The point on the OKLCh plane with the lightness and chroma, the inverse of
+/// [oklch_plane_at].
+pub fn oklch_plane_position(lightness: f64, chroma: f64) -> (f64, f64) {
+    (chroma / OKLCH_PLANE_CHROMA_MAX, 1. - lightness / 100.)
+}
+
+fn point_on_circle(turns: f64, radius: f64) -> (f64, f64) {
+    let angle = turns * TAU;
+    (0.5 + radius * angle.sin(), 0.5 - radius * angle.cos())
+}
+
+/// The left and top edge of the square inside of the wheel, and its size.
+fn wheel_square_bounds() -> (f64, f64) {
+    let size = INNER_RADIUS * SQRT_2;
+    (0.5 - size / 2., size)
+}
+
+/// The vertices of the triangle with the pure hue, white and black, with the
+/// pure hue pointing towards the hue on the ring.
+fn triangle_vertices(hue: f64) -> [[f64; 2]; 3] {
+    [0., 1. / 3., 2. / 3.].map(|offset| {
+        let (x, y) = point_on_circle(hue + offset, INNER_RADIUS);
+        [x, y]
+    })
+}
+
+/// The weights of the triangle vertices for a point, which are all positive
+/// inside of the triangle.
+fn triangle_barycentric(hue: f64, x: f64, y: f64) -> [f64; 3] {
+    let [a, b, c] = triangle_vertices(hue);
+    let determinant =
+        (b[1] - c[1]) * (a[0] - c[0]) + (c[0] - b[0]) * (a[1] - c[1]);
+    let weight_a =
+        ((b[1] - c[1]) * (x - c[0]) + (c[0] - b[0]) * (y - c[1])) / determinant;
+    let weight_b =
+        ((c[1] - a[1]) * (x - c[0]) + (a[0] - c[0]) * (y - c[1])) / determinant;
+    [weight_a, weight_b, 1. - weight_a - weight_b]
+}
+
+/// Move weights of a point outside of the triangle onto its edge.
+fn clamp_barycentric(weights: [f64; 3]) -> [f64; 3] {
+    let clamped = weights.map(|weight| weight.max(0.));
+    let sum = clamped.iter().sum::<f64>();
+    clamped.map(|weight| weight / sum)
+}
@@ -0,0 +1,82 @@
+use super::*;
+
+/// A classic color harmony, made up of colors with hues at fixed angles from
+/// a base color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Harmony {
+    Complementary,
+    SplitComplementary,
+    Analogous,
+    Triadic,
+    Tetradic,
+}
+
+impl Harmony {
+    pub const ALL: [Harmony; 5] = [
+        Harmony::Complementary,
+        Harmony::SplitComplementary,
+        Harmony::Analogous,
+        Harmony::Triadic,
+        Harmony::Tetradic,
+    ];
+
+    /// The hue offsets in degrees of each color in the harmony, where the base
+    /// color is the one at 0.
+    pub fn hue_offsets(&self) -> &'static [f64] {
+        match self {
+            Harmony::Complementary => &[0., 180.],
+            Harmony::SplitComplementary => &[0., 150., 210.],
+            Harmony::Analogous => &[-30., 0., 30.],
+            Harmony::Triadic => &[0., 120., 240.],
+            Harmony::Tetradic => &[0., 60., 180., 240.],
+        }
+    }
+
+    /// Generate the colors of the harmony from a base color, rotating the hue
+    /// as measured in `hue_space`.
+    ///
+    /// Returns `None` if `hue_space` doesn't have a hue component.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// let red = DynamicColor::new((255., 0., 0.), ColorSpace::Rgb);
+    /// let hex_codes = Harmony::Triadic
+    ///     .generate(red, ColorSpace::Hsv)
+    ///     .unwrap()
+    ///     .into_iter()
+    ///     .map(|color| color.to_color::<Rgb>().as_hex_code())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(hex_codes, vec!["ff0000", "00ff00", "0000ff"]);
+    /// ```
+    pub fn generate(
+        &self,
+        base: DynamicColor,
+        hue_space: ColorSpace,
+    ) -> Option<Vec<DynamicColor>> {
+        self.hue_offsets()
+            .iter()
+            .map(|offset| match *offset == 0. {
+                true => Some(base),
+                false => base.rotate_hue(*offset, hue_space),
+            })
+            .collect()
+    }
+}
+
+impl UiDisplay for Harmony {
+    type Environment = ();
+
+    fn to_ui_string(&self, _env: Self::Environment) -> String {
+        match self {
+            Harmony::Complementary => "Complementary",
+            Harmony::SplitComplementary => "Split Complementary",
+            Harmony::Analogous => "Analogous",
+            Harmony::Triadic => "Triadic",
+            Harmony::Tetradic => "Tetradic",
+        }
+        .to_owned()
+    }
+}
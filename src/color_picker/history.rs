@@ -0,0 +1,98 @@
+use std::collections::VecDeque;
+
+/// A bounded undo/redo history of committed values.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+///
+/// let mut history = History::new(1);
+/// history.commit(1);
+/// history.commit(2);
+/// history.commit(3);
+///
+/// assert_eq!(history.undo(), Some(2));
+/// // The oldest value was dropped to stay within the capacity.
+/// assert_eq!(history.undo(), None);
+/// assert_eq!(history.redo(), Some(3));
+///
+/// history.undo();
+/// history.commit(4);
+/// // Committing discards the undone values.
+/// assert_eq!(history.redo(), None);
+/// assert_eq!(history.recent().collect::<Vec<_>>(), vec![&4, &2]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct History<T> {
+    past: VecDeque<T>,
+    present: Option<T>,
+    future: Vec<T>,
+    /// How many values are kept before the present one.
+    capacity: usize,
+}
+
+impl<T: Clone + PartialEq> History<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            past: VecDeque::new(),
+            present: None,
+            future: vec![],
+            capacity,
+        }
+    }
+
+    pub fn present(&self) -> Option<&T> {
+        self.present.as_ref()
+    }
+
+    /// Record a new value as the present one. Nothing happens if it's equal
+    /// to the present value.
+    ///
+    /// Returns if the value was recorded.
+    pub fn commit(&mut self, value: T) -> bool {
+        if self.present.as_ref() == Some(&value) {
+            return false;
+        }
+
+        if let Some(present) = self.present.replace(value) {
+            self.past.push_back(present);
+            if self.past.len() > self.capacity {
+                self.past.pop_front();
+            }
+        }
+        self.future.clear();
+
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.past.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+
+    /// Step back to the previous value, returning it.
+    pub fn undo(&mut self) -> Option<T> {
+        let previous = self.past.pop_back()?;
+        if let Some(present) = self.present.replace(previous.clone()) {
+            self.future.push(present);
+        }
+        Some(previous)
+    }
+
+    /// Step forward to the value that was last undone, returning it.
+    pub fn redo(&mut self) -> Option<T> {
+        let next = self.future.pop()?;
+        if let Some(present) = self.present.replace(next.clone()) {
+            self.past.push_back(present);
+        }
+        Some(next)
+    }
+
+    /// The present and past values, newest first.
+    pub fn recent(&self) -> impl Iterator<Item = &T> {
+        self.present.iter().chain(self.past.iter().rev())
+    }
+}
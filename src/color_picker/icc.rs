@@ -0,0 +1,1234 @@
+//! Parsing of ICC v2 and v4 profiles, and conversion of colors through them.
+//!
+//! Only the parts of the specification that are needed to convert a single
+//! color are implemented: matrix/TRC and gray TRC profiles, as well as the
+//! `lut8`, `lut16`, `lutAtoB` and `lutBtoA` tag types.
+//!
+//! Specification: https://www.color.org/specification/ICC.1-2022-05.pdf
+
+use super::*;
+use crate::utils::*;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IccError {
+    /// The data ended before the structure being read did.
+    UnexpectedEnd,
+    /// The `acsp` file signature is missing.
+    InvalidSignature,
+    UnsupportedVersion(u8),
+    UnsupportedTagType([u8; 4]),
+    /// The profile doesn't contain the tags required to convert in the
+    /// requested direction.
+    MissingTransform,
+    Malformed(&'static str),
+}
+
+impl Display for IccError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IccError::UnexpectedEnd => write!(f, "the profile is truncated"),
+            IccError::InvalidSignature => {
+                write!(f, "the file isn't an ICC profile")
+            }
+            IccError::UnsupportedVersion(version) => {
+                write!(f, "ICC version {} isn't supported", version)
+            }
+            IccError::UnsupportedTagType(signature) => write!(
+                f,
+                "the tag type '{}' isn't supported",
+                String::from_utf8_lossy(signature)
+            ),
+            IccError::MissingTransform => write!(
+                f,
+                "the profile doesn't contain a transform for this direction"
+            ),
+            IccError::Malformed(reason) => {
+                write!(f, "the profile is malformed: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for IccError {}
+
+/// The rendering intent used when converting colors between gamuts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RenderingIntent {
+    Perceptual,
+    RelativeColorimetric,
+    Saturation,
+    AbsoluteColorimetric,
+}
+
+impl RenderingIntent {
+    /// The index of the `AToB` and `BToA` tags used for this intent.
+    fn tag_index(&self) -> u8 {
+        match self {
+            RenderingIntent::Perceptual => 0,
+            RenderingIntent::RelativeColorimetric => 1,
+            RenderingIntent::Saturation => 2,
+            RenderingIntent::AbsoluteColorimetric => 1,
+        }
+    }
+}
+
+impl UiDisplay for RenderingIntent {
+    type Environment = ();
+
+    fn to_ui_string(&self, _env: Self::Environment) -> String {
+        match self {
+            RenderingIntent::Perceptual => "Perceptual",
+            RenderingIntent::RelativeColorimetric => "Relative",
+            RenderingIntent::Saturation => "Saturation",
+            RenderingIntent::AbsoluteColorimetric => "Absolute",
+        }
+        .to_owned()
+    }
+}
+
+impl Display for RenderingIntent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RenderingIntent::Perceptual => "Perceptual",
+            RenderingIntent::RelativeColorimetric => "RelativeColorimetric",
+            RenderingIntent::Saturation => "Saturation",
+            RenderingIntent::AbsoluteColorimetric => "AbsoluteColorimetric",
+        })
+    }
+}
+
+impl FromStr for RenderingIntent {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Perceptual" => Ok(RenderingIntent::Perceptual),
+            "RelativeColorimetric" => Ok(RenderingIntent::RelativeColorimetric),
+            "Saturation" => Ok(RenderingIntent::Saturation),
+            "AbsoluteColorimetric" => Ok(RenderingIntent::AbsoluteColorimetric),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The color space of the device values of a profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IccColorSpace {
+    Gray,
+    Rgb,
+    Cmy,
+    Cmyk,
+    Lab,
+    Xyz,
+    /// A color space with the specified amount of channels which has no
+    /// special treatment, like `6CLR`.
+    Other([u8; 4], usize),
+}
+
+impl IccColorSpace {
+    fn from_signature(signature: [u8; 4]) -> Self {
+        match &signature {
+            b"GRAY" => IccColorSpace::Gray,
+            b"RGB " => IccColorSpace::Rgb,
+            b"CMY " => IccColorSpace::Cmy,
+            b"CMYK" => IccColorSpace::Cmyk,
+            b"Lab " => IccColorSpace::Lab,
+            b"XYZ " => IccColorSpace::Xyz,
+            [count @ b'2'..=b'9', b'C', b'L', b'R'] => {
+                IccColorSpace::Other(signature, (count - b'0') as usize)
+            }
+            [count @ b'A'..=b'F', b'C', b'L', b'R'] => {
+                IccColorSpace::Other(signature, (count - b'A' + 10) as usize)
+            }
+            _ => IccColorSpace::Other(signature, 3),
+        }
+    }
+
+    pub fn channel_count(&self) -> usize {
+        match self {
+            IccColorSpace::Gray => 1,
+            IccColorSpace::Rgb
+            | IccColorSpace::Cmy
+            | IccColorSpace::Lab
+            | IccColorSpace::Xyz => 3,
+            IccColorSpace::Cmyk => 4,
+            IccColorSpace::Other(_, count) => *count,
+        }
+    }
+
+    /// Short labels for each channel, like `"C"`, `"M"`, `"Y"`, and `"K"`.
+    pub fn channel_labels(&self) -> Vec<String> {
+        let labels: &[&str] = match self {
+            IccColorSpace::Gray => &["K"],
+            IccColorSpace::Rgb => &["R", "G", "B"],
+            IccColorSpace::Cmy => &["C", "M", "Y"],
+            IccColorSpace::Cmyk => &["C", "M", "Y", "K"],
+            IccColorSpace::Lab => &["L", "a", "b"],
+            IccColorSpace::Xyz => &["X", "Y", "Z"],
+            IccColorSpace::Other(_, count) => {
+                return (1..=*count).map(|i| i.to_string()).collect();
+            }
+        };
+
+        labels.iter().map(|label| (*label).to_owned()).collect()
+    }
+}
+
+impl UiDisplay for IccColorSpace {
+    type Environment = ();
+
+    fn to_ui_string(&self, _env: Self::Environment) -> String {
+        match self {
+            IccColorSpace::Gray => "Gray".to_owned(),
+            IccColorSpace::Rgb => "RGB".to_owned(),
+            IccColorSpace::Cmy => "CMY".to_owned(),
+            IccColorSpace::Cmyk => "CMYK".to_owned(),
+            IccColorSpace::Lab => "Lab".to_owned(),
+            IccColorSpace::Xyz => "XYZ".to_owned(),
+            IccColorSpace::Other(signature, _) => {
+                String::from_utf8_lossy(signature).trim().to_owned()
+            }
+        }
+    }
+}
+
+/// A one dimensional transfer function.
+#[derive(Debug, Clone, PartialEq)]
+enum Curve {
+    Identity,
+    Gamma(f64),
+    /// Evenly spaced samples in the range 0 to 1.
+    Table(Vec<f64>),
+    /// The parametric function type and its parameters (`g`, `a`, `b`, ...),
+    /// padded with zeros.
+    Parametric(u16, [f64; 7]),
+}
+
+impl Curve {
+    fn apply(&self, x: f64) -> f64 {
+        let x = x.clamp(0., 1.);
+        match self {
+            Curve::Identity => x,
+            Curve::Gamma(gamma) => x.powf(*gamma),
+            Curve::Table(table) => interpolate_table(table, x),
+            Curve::Parametric(kind, [g, a, b, c, d, e, f]) => {
+                let power = |base: f64| base.max(0.).powf(*g);
+                match kind {
+                    0 => power(x),
+                    1 if x >= -b / a => power(a * x + b),
+                    1 => 0.,
+                    2 if x >= -b / a => power(a * x + b) + c,
+                    2 => *c,
+                    3 if x >= *d => power(a * x + b),
+                    3 => c * x,
+                    _ if x >= *d => power(a * x + b) + e,
+                    _ => c * x + f,
+                }
+            }
+        }
+        .clamp(0., 1.)
+    }
+
+    /// Solve for the input that results in `y`, assuming that the curve is
+    /// monotonic.
+    fn invert(&self, y: f64) -> f64 {
+        let y = y.clamp(0., 1.);
+        match self {
+            Curve::Identity => y,
+            Curve::Gamma(gamma) => y.powf(1. / gamma),
+            _ => {
+                let increasing = self.apply(1.) >= self.apply(0.);
+
+                let (mut low, mut high) = (0., 1.);
+                for _ in 0..40 {
+                    let middle = (low + high) / 2.;
+                    if (self.apply(middle) < y) == increasing {
+                        low = middle;
+                    } else {
+                        high = middle;
+                    }
+                }
+                (low + high) / 2.
+            }
+        }
+    }
+}
+
+fn interpolate_table(table: &[f64], x: f64) -> f64 {
+    match table.len() {
+        0 => x,
+        1 => table[0],
+        len => {
+            let position = x * (len - 1) as f64;
+            let index = (position.floor() as usize).min(len - 2);
+            let t = position - index as f64;
+
+            table[index] * (1. - t) + table[index + 1] * t
+        }
+    }
+}
+
+/// A multidimensional color lookup table.
+#[derive(Debug, Clone, PartialEq)]
+struct Clut {
+    grid_points: Vec<usize>,
+    output_channels: usize,
+    /// Samples normalized to the range 0 to 1, where the first input channel
+    /// varies the slowest.
+    values: Vec<f64>,
+}
+
+impl Clut {
+    /// Read a table of `bytes_per_entry` sized samples at `offset`, rejecting
+    /// grids without any points and tables that don't fit into `data`.
+    fn parse(
+        data: &[u8],
+        offset: usize,
+        grid_points: Vec<usize>,
+        output_channels: usize,
+        bytes_per_entry: usize,
+    ) -> Result<Self, IccError> {
+        if grid_points.contains(&0) {
+            return Err(IccError::Malformed(
+                "lookup table without grid points",
+            ));
+        }
+
+        let too_large = IccError::Malformed("lookup table is too large");
+        let count = grid_points
+            .iter()
+            .try_fold(output_channels, |count, points| {
+                count.checked_mul(*points)
+            })
+            .ok_or(too_large.clone())?;
+        let end = count
+            .checked_mul(bytes_per_entry)
+            .and_then(|size| size.checked_add(offset))
+            .ok_or(too_large)?;
+        if end > data.len() {
+            return Err(IccError::UnexpectedEnd);
+        }
+
+        Ok(Self {
+            grid_points,
+            output_channels,
+            values: parse_table(data, offset, count, bytes_per_entry)?,
+        })
+    }
+
+    /// Sample the table using multilinear interpolation.
+    fn apply(&self, input: &[f64]) -> Vec<f64> {
+        let dimensions = self.grid_points.len();
+
+        let mut base_indices = Vec::with_capacity(dimensions);
+        let mut fractions = Vec::with_capacity(dimensions);
+        for (value, points) in input.iter().zip(&self.grid_points) {
+            let position = value.clamp(0., 1.) * (points - 1) as f64;
+            let index =
+                (position.floor() as usize).min(points.saturating_sub(2));
+            base_indices.push(index);
+            fractions.push(position - index as f64);
+        }
+
+        let mut output = vec![0.; self.output_channels];
+        for corner in 0..(1_usize << dimensions) {
+            let mut weight = 1.;
+            let mut offset = 0;
+            for dimension in 0..dimensions {
+                let upper = (corner >> (dimensions - 1 - dimension)) & 1 == 1;
+                let points = self.grid_points[dimension];
+                let index =
+                    (base_indices[dimension] + upper as usize).min(points - 1);
+
+                weight *= match upper {
+                    true => fractions[dimension],
+                    false => 1. - fractions[dimension],
+                };
+                offset = offset * points + index;
+            }
+
+            if weight == 0. {
+                continue;
+            }
+
+            let start = offset * self.output_channels;
+            for (channel, value) in output.iter_mut().enumerate() {
+                *value += weight * self.values[start + channel];
+            }
+        }
+
+        output
+    }
+}
+
+/// The different ways the profile connection space can be encoded in the
+/// normalized inputs and outputs of a lookup table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PcsEncoding {
+    Xyz,
+    Lab,
+    /// The legacy 16-bit Lab encoding used by `lut16` tags.
+    LegacyLab,
+}
+
+impl PcsEncoding {
+    fn encode(&self, xyz: (f64, f64, f64)) -> [f64; 3] {
+        const LEGACY_FACTOR: f64 = 65280. / 65535.;
+        match self {
+            PcsEncoding::Xyz => {
+                const FACTOR: f64 = 32768. / 65535.;
+                [xyz.0 * FACTOR, xyz.1 * FACTOR, xyz.2 * FACTOR]
+            }
+            PcsEncoding::Lab | PcsEncoding::LegacyLab => {
+                let (l, a, b) = xyz_to_lab(xyz, WHITE_D50);
+                let encoded = [l / 100., (a + 128.) / 255., (b + 128.) / 255.];
+                match self {
+                    // The legacy encoding maps the maximum values to 0xff00
+                    // instead of 0xffff.
+                    PcsEncoding::LegacyLab => {
+                        encoded.map(|value| value * LEGACY_FACTOR)
+                    }
+                    _ => encoded,
+                }
+            }
+        }
+    }
+
+    fn decode(&self, values: &[f64]) -> (f64, f64, f64) {
+        const LEGACY_FACTOR: f64 = 65535. / 65280.;
+        let values = [values[0], values[1], values[2]];
+        match self {
+            PcsEncoding::Xyz => {
+                const FACTOR: f64 = 65535. / 32768.;
+                (values[0] * FACTOR, values[1] * FACTOR, values[2] * FACTOR)
+            }
+            PcsEncoding::Lab | PcsEncoding::LegacyLab => {
+                let values = match self {
+                    PcsEncoding::LegacyLab => {
+                        values.map(|value| value * LEGACY_FACTOR)
+                    }
+                    _ => values,
+                };
+                lab_to_xyz(
+                    (
+                        values[0] * 100.,
+                        values[1] * 255. - 128.,
+                        values[2] * 255. - 128.,
+                    ),
+                    WHITE_D50,
+                )
+            }
+        }
+    }
+}
+
+/// A transform from device values to the profile connection space, or the
+/// other way around.
+#[derive(Debug, Clone, PartialEq)]
+enum Transform {
+    /// The matrix maps linear device values to XYZ.
+    MatrixTrc {
+        matrix: Matrix3,
+        curves: [Curve; 3],
+    },
+    Gray(Curve),
+    Lut(Lut),
+}
+
+/// A lookup table based pipeline, covering all of `lut8`, `lut16`, `lutAtoB`
+/// and `lutBtoA`.
+///
+/// The stages are always applied in the order they are stored in, which
+/// matches the order for the device to PCS direction. A `lutBtoA` is stored in
+/// reverse by the parser to fit this.
+#[derive(Debug, Clone, PartialEq)]
+struct Lut {
+    input_channels: usize,
+    output_channels: usize,
+    /// Applied before everything else, only used by `lut8` and `lut16`
+    /// converting from XYZ.
+    input_matrix: Option<Matrix3>,
+    input_curves: Vec<Curve>,
+    clut: Option<Clut>,
+    middle_curves: Vec<Curve>,
+    /// A matrix followed by an offset.
+    matrix: Option<(Matrix3, [f64; 3])>,
+    output_curves: Vec<Curve>,
+    /// The stage order of `lutBtoA`, where the matrix comes before the lookup
+    /// table.
+    matrix_first: bool,
+    pcs_encoding: PcsEncoding,
+}
+
+impl Lut {
+    fn apply(&self, input: &[f64]) -> Vec<f64> {
+        let mut values = input.to_vec();
+
+        let apply_curves = |values: &mut Vec<f64>, curves: &[Curve]| {
+            for (value, curve) in values.iter_mut().zip(curves) {
+                *value = curve.apply(*value);
+            }
+        };
+        let apply_matrix = |values: &mut Vec<f64>| {
+            let Some((matrix, offset)) = &self.matrix else {
+                return;
+            };
+            let result = multiply_matrix_vector(
+                matrix,
+                (values[0], values[1], values[2]),
+            );
+            *values = vec![
+                (result.0 + offset[0]).clamp(0., 1.),
+                (result.1 + offset[1]).clamp(0., 1.),
+                (result.2 + offset[2]).clamp(0., 1.),
+            ];
+        };
+
+        if let Some(matrix) = &self.input_matrix {
+            let result = multiply_matrix_vector(
+                matrix,
+                (values[0], values[1], values[2]),
+            );
+            values = vec![result.0, result.1, result.2];
+        }
+
+        apply_curves(&mut values, &self.input_curves);
+        if self.matrix_first {
+            apply_matrix(&mut values);
+            apply_curves(&mut values, &self.middle_curves);
+        }
+        if let Some(clut) = &self.clut {
+            values = clut.apply(&values);
+        }
+        if !self.matrix_first {
+            apply_curves(&mut values, &self.middle_curves);
+            apply_matrix(&mut values);
+        }
+        apply_curves(&mut values, &self.output_curves);
+
+        values.resize(self.output_channels, 0.);
+        values
+    }
+}
+
+/// A parsed ICC profile.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+///
+/// assert_eq!(IccProfile::parse(&[0; 64]), Err(IccError::UnexpectedEnd));
+/// assert_eq!(IccProfile::parse(&[0; 132]), Err(IccError::InvalidSignature));
+/// ```
+///
+/// Profiles of sRGB, one made of a matrix and tone curves and one made of
+/// lookup tables, convert sRGB colors to the same device values:
+/// ```
+/// use lax_utils::color_picker::*;
+/// use approx::assert_relative_eq;
+///
+/// let profiles = [
+///     &include_bytes!("../../tests/profiles/srgb-matrix-trc.icc")[..],
+///     &include_bytes!("../../tests/profiles/srgb-lut.icc")[..],
+/// ];
+/// let orange = Rgb::from_components((255., 136., 0.));
+/// let intent = RenderingIntent::RelativeColorimetric;
+///
+/// for bytes in profiles {
+///     let profile = IccProfile::parse(bytes).unwrap();
+///     assert_eq!(profile.color_space(), IccColorSpace::Rgb);
+///
+///     let device = profile.rgb_to_device(orange, intent).unwrap();
+///     assert_relative_eq!(device[0], 1., epsilon = 0.005);
+///     assert_relative_eq!(device[1], 136. / 255., epsilon = 0.005);
+///     assert_relative_eq!(device[2], 0., epsilon = 0.005);
+///
+///     let rgb = profile.device_to_rgb(&device, intent).unwrap();
+///     assert_eq!(rgb.as_hex_code(), "ff8800".to_owned());
+/// }
+/// ```
+///
+/// Malformed profiles result in errors instead of panics, including tags that
+/// don't belong to the color space of the profile:
+/// ```
+/// use lax_utils::color_picker::*;
+///
+/// let profiles = [
+///     &include_bytes!("../../tests/profiles/srgb-matrix-trc.icc")[..],
+///     &include_bytes!("../../tests/profiles/srgb-lut.icc")[..],
+/// ];
+/// let orange = Rgb::from_components((255., 136., 0.));
+/// let intent = RenderingIntent::Perceptual;
+///
+/// for bytes in profiles {
+///     for index in 0..bytes.len() {
+///         for value in [0x00, 0x01, 0xff] {
+///             let mut bytes = bytes.to_vec();
+///             bytes[index] = value;
+///             if let Ok(profile) = IccProfile::parse(&bytes) {
+///                 let _ = profile.rgb_to_device(orange, intent);
+///                 let _ = profile.device_to_rgb(&[0.5; 3], intent);
+///             }
+///         }
+///     }
+/// }
+///
+/// let gray = IccProfile::parse(include_bytes!(
+///     "../../tests/profiles/gray-with-rgb-tags.icc"
+/// ))
+/// .unwrap();
+/// assert_eq!(gray.color_space(), IccColorSpace::Gray);
+/// assert_eq!(
+///     gray.device_to_rgb(&[0.5], intent),
+///     Err(IccError::MissingTransform)
+/// );
+/// assert_eq!(
+///     gray.rgb_to_device(orange, intent),
+///     Err(IccError::MissingTransform)
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct IccProfile {
+    version: (u8, u8),
+    device_class: [u8; 4],
+    color_space: IccColorSpace,
+    description: Option<String>,
+    media_white: (f64, f64, f64),
+    to_pcs: HashMap<u8, Transform>,
+    from_pcs: HashMap<u8, Transform>,
+}
+
+impl IccProfile {
+    pub fn parse(bytes: &[u8]) -> Result<Self, IccError> {
+        if bytes.len() < 132 {
+            return Err(IccError::UnexpectedEnd);
+        }
+        if read_signature(bytes, 36)? != *b"acsp" {
+            return Err(IccError::InvalidSignature);
+        }
+
+        let version = (bytes[8], bytes[9] >> 4);
+        if !(2..=4).contains(&version.0) {
+            return Err(IccError::UnsupportedVersion(version.0));
+        }
+
+        let device_class = read_signature(bytes, 12)?;
+        let color_space =
+            IccColorSpace::from_signature(read_signature(bytes, 16)?);
+        let pcs = read_signature(bytes, 20)?;
+        let pcs_is_lab = &pcs == b"Lab ";
+
+        let tag_count = read_u32(bytes, 128)? as usize;
+        let mut tags = HashMap::new();
+        for i in 0..tag_count {
+            let entry = 132 + i * 12;
+            let signature = read_signature(bytes, entry)?;
+            let offset = read_u32(bytes, entry + 4)? as usize;
+            let size = read_u32(bytes, entry + 8)? as usize;
+
+            let data = bytes
+                .get(offset..offset.saturating_add(size))
+                .ok_or(IccError::UnexpectedEnd)?;
+            tags.insert(signature, data);
+        }
+
+        let description = tags
+            .get(b"desc")
+            .and_then(|data| parse_text(data).ok())
+            .filter(|text| !text.is_empty());
+
+        let media_white = match tags.get(b"wtpt") {
+            Some(data) => parse_xyz(data)?,
+            None => WHITE_D50,
+        };
+
+        let channels = color_space.channel_count();
+        let mut to_pcs = HashMap::new();
+        let mut from_pcs = HashMap::new();
+        for index in 0..3 {
+            let a_to_b = [b'A', b'2', b'B', b'0' + index];
+            if let Some(data) = tags.get(&a_to_b) {
+                let lut = parse_lut(data, false, pcs_is_lab)?;
+                if lut.input_channels != channels || lut.output_channels != 3 {
+                    return Err(IccError::Malformed(
+                        "AToB channel count mismatch",
+                    ));
+                }
+                to_pcs.insert(index, Transform::Lut(lut));
+            }
+
+            let b_to_a = [b'B', b'2', b'A', b'0' + index];
+            if let Some(data) = tags.get(&b_to_a) {
+                let lut = parse_lut(data, true, pcs_is_lab)?;
+                if lut.input_channels != 3 || lut.output_channels != channels {
+                    return Err(IccError::Malformed(
+                        "BToA channel count mismatch",
+                    ));
+                }
+                from_pcs.insert(index, Transform::Lut(lut));
+            }
+        }
+
+        // The tags of matrix/TRC and gray profiles only apply to their color
+        // spaces, and are ignored for others to not read missing channels.
+        let matrix_trc: Option<Transform> = try {
+            (color_space == IccColorSpace::Rgb).then_some(())?;
+            let columns = [
+                parse_xyz(tags.get(b"rXYZ")?).ok()?,
+                parse_xyz(tags.get(b"gXYZ")?).ok()?,
+                parse_xyz(tags.get(b"bXYZ")?).ok()?,
+            ];
+            let matrix = [
+                [columns[0].0, columns[1].0, columns[2].0],
+                [columns[0].1, columns[1].1, columns[2].1],
+                [columns[0].2, columns[1].2, columns[2].2],
+            ];
+            let curves = [
+                parse_curve(tags.get(b"rTRC")?).ok()?.0,
+                parse_curve(tags.get(b"gTRC")?).ok()?.0,
+                parse_curve(tags.get(b"bTRC")?).ok()?.0,
+            ];
+
+            Transform::MatrixTrc { matrix, curves }
+        };
+        let gray_trc = tags
+            .get(b"kTRC")
+            .filter(|_| color_space == IccColorSpace::Gray)
+            .and_then(|data| parse_curve(data).ok())
+            .map(|(curve, _)| Transform::Gray(curve));
+
+        // Matrix/TRC and gray profiles are only used as a fallback for any
+        // intents without lookup tables, as specified.
+        if let Some(transform) = matrix_trc.or(gray_trc) {
+            for index in 0..3 {
+                to_pcs.entry(index).or_insert_with(|| transform.clone());
+                from_pcs.entry(index).or_insert_with(|| transform.clone());
+            }
+        }
+
+        Ok(Self {
+            version,
+            device_class,
+            color_space,
+            description,
+            media_white,
+            to_pcs,
+            from_pcs,
+        })
+    }
+
+    /// The major and minor version of the profile.
+    pub fn version(&self) -> (u8, u8) {
+        self.version
+    }
+
+    /// The device class signature of the profile, like `prtr` for printers or
+    /// `mntr` for monitors.
+    pub fn device_class(&self) -> String {
+        String::from_utf8_lossy(&self.device_class)
+            .trim()
+            .to_owned()
+    }
+
+    pub fn color_space(&self) -> IccColorSpace {
+        self.color_space
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    fn transform(
+        transforms: &HashMap<u8, Transform>,
+        intent: RenderingIntent,
+    ) -> Result<&Transform, IccError> {
+        transforms
+            .get(&intent.tag_index())
+            .or_else(|| transforms.get(&0))
+            .ok_or(IccError::MissingTransform)
+    }
+
+    /// Convert a PCS color, in D50 relative XYZ, to device values in the range
+    /// 0 to 1.
+    pub fn pcs_to_device(
+        &self,
+        xyz: (f64, f64, f64),
+        intent: RenderingIntent,
+    ) -> Result<Vec<f64>, IccError> {
+        let xyz = match intent {
+            RenderingIntent::AbsoluteColorimetric => (
+                xyz.0 * WHITE_D50.0 / self.media_white.0,
+                xyz.1 * WHITE_D50.1 / self.media_white.1,
+                xyz.2 * WHITE_D50.2 / self.media_white.2,
+            ),
+            _ => xyz,
+        };
+
+        Ok(match Self::transform(&self.from_pcs, intent)? {
+            Transform::MatrixTrc { matrix, curves } => {
+                let inverse = invert_matrix(matrix).ok_or(
+                    IccError::Malformed("the colorant matrix isn't invertible"),
+                )?;
+                let linear = multiply_matrix_vector(&inverse, xyz);
+
+                vec![
+                    curves[0].invert(linear.0),
+                    curves[1].invert(linear.1),
+                    curves[2].invert(linear.2),
+                ]
+            }
+            Transform::Gray(curve) => vec![curve.invert(xyz.1)],
+            Transform::Lut(lut) => lut.apply(&lut.pcs_encoding.encode(xyz)),
+        })
+    }
+
+    /// Convert device values in the range 0 to 1 to a PCS color, in D50
+    /// relative XYZ.
+    pub fn device_to_pcs(
+        &self,
+        device: &[f64],
+        intent: RenderingIntent,
+    ) -> Result<(f64, f64, f64), IccError> {
+        if device.len() != self.color_space.channel_count() {
+            return Err(IccError::Malformed("wrong amount of device values"));
+        }
+
+        let xyz = match Self::transform(&self.to_pcs, intent)? {
+            Transform::MatrixTrc { matrix, curves } => {
+                let linear = (
+                    curves[0].apply(device[0]),
+                    curves[1].apply(device[1]),
+                    curves[2].apply(device[2]),
+                );
+                multiply_matrix_vector(matrix, linear)
+            }
+            Transform::Gray(curve) => {
+                let y = curve.apply(device[0]);
+                (WHITE_D50.0 * y, y, WHITE_D50.2 * y)
+            }
+            Transform::Lut(lut) => lut.pcs_encoding.decode(&lut.apply(device)),
+        };
+
+        Ok(match intent {
+            RenderingIntent::AbsoluteColorimetric => (
+                xyz.0 * self.media_white.0 / WHITE_D50.0,
+                xyz.1 * self.media_white.1 / WHITE_D50.1,
+                xyz.2 * self.media_white.2 / WHITE_D50.2,
+            ),
+            _ => xyz,
+        })
+    }
+
+    /// Convert an sRGB color into the device values of this profile.
+    pub fn rgb_to_device(
+        &self,
+        rgb: Rgb,
+        intent: RenderingIntent,
+    ) -> Result<Vec<f64>, IccError> {
+        let xyz = multiply_matrix_vector(
+            &chromatic_adaptation_matrix(WHITE_D65, WHITE_D50),
+            Xyz::from_rgb(rgb).as_tuple(),
+        );
+
+        self.pcs_to_device(xyz, intent)
+    }
+
+    /// Convert device values of this profile into sRGB, clipping colors
+    /// outside of the sRGB gamut.
+    pub fn device_to_rgb(
+        &self,
+        device: &[f64],
+        intent: RenderingIntent,
+    ) -> Result<Rgb, IccError> {
+        let xyz = multiply_matrix_vector(
+            &chromatic_adaptation_matrix(WHITE_D50, WHITE_D65),
+            self.device_to_pcs(device, intent)?,
+        );
+
+        Ok(Xyz::from_tuple(xyz).as_rgb())
+    }
+}
+
+impl DynamicColor {
+    /// Convert this color into the device values of an ICC profile, in the
+    /// range 0 to 1.
+    pub fn to_icc_device_values(
+        &self,
+        profile: &IccProfile,
+        intent: RenderingIntent,
+    ) -> Result<Vec<f64>, IccError> {
+        profile.rgb_to_device(self.to_color::<Rgb>(), intent)
+    }
+}
+
+fn read_bytes<const N: usize>(
+    bytes: &[u8],
+    offset: usize,
+) -> Result<[u8; N], IccError> {
+    let end = offset.checked_add(N).ok_or(IccError::UnexpectedEnd)?;
+    bytes
+        .get(offset..end)
+        .and_then(|slice| slice.try_into().ok())
+        .ok_or(IccError::UnexpectedEnd)
+}
+
+fn read_signature(bytes: &[u8], offset: usize) -> Result<[u8; 4], IccError> {
+    read_bytes::<4>(bytes, offset)
+}
+
+fn read_u8(bytes: &[u8], offset: usize) -> Result<u8, IccError> {
+    bytes.get(offset).copied().ok_or(IccError::UnexpectedEnd)
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, IccError> {
+    read_bytes::<2>(bytes, offset).map(u16::from_be_bytes)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, IccError> {
+    read_bytes::<4>(bytes, offset).map(u32::from_be_bytes)
+}
+
+fn read_s15_fixed16(bytes: &[u8], offset: usize) -> Result<f64, IccError> {
+    read_bytes::<4>(bytes, offset)
+        .map(|bytes| i32::from_be_bytes(bytes) as f64 / 65536.)
+}
+
+fn read_matrix(bytes: &[u8], offset: usize) -> Result<Matrix3, IccError> {
+    let mut matrix = [[0.; 3]; 3];
+    for (i, row) in matrix.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
+            *cell = read_s15_fixed16(bytes, offset + (i * 3 + j) * 4)?;
+        }
+    }
+    Ok(matrix)
+}
+
+fn parse_xyz(data: &[u8]) -> Result<(f64, f64, f64), IccError> {
+    match &read_signature(data, 0)? {
+        b"XYZ " => Ok((
+            read_s15_fixed16(data, 8)?,
+            read_s15_fixed16(data, 12)?,
+            read_s15_fixed16(data, 16)?,
+        )),
+        signature => Err(IccError::UnsupportedTagType(*signature)),
+    }
+}
+
+/// Parse a `textDescriptionType`, `multiLocalizedUnicodeType` or `textType`.
+/// The first localization is used for the latter.
+fn parse_text(data: &[u8]) -> Result<String, IccError> {
+    let trim = |text: String| text.trim_end_matches('\0').trim().to_owned();
+
+    match &read_signature(data, 0)? {
+        b"desc" => {
+            let length = read_u32(data, 8)? as usize;
+            let text = 12_usize
+                .checked_add(length)
+                .and_then(|end| data.get(12..end))
+                .ok_or(IccError::UnexpectedEnd)?;
+            Ok(trim(String::from_utf8_lossy(text).into_owned()))
+        }
+        b"text" => {
+            let text = data.get(8..).ok_or(IccError::UnexpectedEnd)?;
+            Ok(trim(String::from_utf8_lossy(text).into_owned()))
+        }
+        b"mluc" => {
+            if read_u32(data, 8)? == 0 {
+                return Ok(String::new());
+            }
+            let length = read_u32(data, 20)? as usize;
+            let offset = read_u32(data, 24)? as usize;
+            let text = offset
+                .checked_add(length)
+                .and_then(|end| data.get(offset..end))
+                .ok_or(IccError::UnexpectedEnd)?
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect::<Vec<_>>();
+            Ok(trim(String::from_utf16_lossy(&text)))
+        }
+        signature => Err(IccError::UnsupportedTagType(*signature)),
+    }
+}
+
+/// Parse a `curveType` or `parametricCurveType`, returning the curve and the
+/// amount of bytes it takes up, padded to a multiple of four.
+fn parse_curve(data: &[u8]) -> Result<(Curve, usize), IccError> {
+    let padded = |size: usize| size.div_ceil(4) * 4;
+
+    match &read_signature(data, 0)? {
+        b"curv" => {
+            let count = read_u32(data, 8)? as usize;
+            let curve = match count {
+                0 => Curve::Identity,
+                1 => Curve::Gamma(read_u16(data, 12)? as f64 / 256.),
+                _ => Curve::Table(
+                    (0..count)
+                        .map(|i| {
+                            read_u16(data, 12 + i * 2)
+                                .map(|value| value as f64 / 65535.)
+                        })
+                        .collect::<Result<_, _>>()?,
+                ),
+            };
+            Ok((curve, padded(12 + count * 2)))
+        }
+        b"para" => {
+            let kind = read_u16(data, 8)?;
+            let parameter_count = match kind {
+                0 => 1,
+                1 => 3,
+                2 => 4,
+                3 => 5,
+                4 => 7,
+                _ => {
+                    return Err(IccError::Malformed("unknown parametric curve"))
+                }
+            };
+            let mut parameters = [0.; 7];
+            for (i, parameter) in
+                parameters.iter_mut().take(parameter_count).enumerate()
+            {
+                *parameter = read_s15_fixed16(data, 12 + i * 4)?;
+            }
+            Ok((
+                Curve::Parametric(kind, parameters),
+                padded(12 + parameter_count * 4),
+            ))
+        }
+        signature => Err(IccError::UnsupportedTagType(*signature)),
+    }
+}
+
+/// Parse `count` consecutive curves starting at `offset`.
+fn parse_curves(
+    data: &[u8],
+    offset: usize,
+    count: usize,
+) -> Result<Vec<Curve>, IccError> {
+    let mut offset = offset;
+    let mut curves = Vec::with_capacity(count);
+    for _ in 0..count {
+        let (curve, size) =
+            parse_curve(data.get(offset..).ok_or(IccError::UnexpectedEnd)?)?;
+        curves.push(curve);
+        offset += size;
+    }
+    Ok(curves)
+}
+
+/// Parse the table of a `lut8Type` or `lut16Type`, with `bytes_per_entry`
+/// being either 1 or 2.
+fn parse_table(
+    data: &[u8],
+    offset: usize,
+    count: usize,
+    bytes_per_entry: usize,
+) -> Result<Vec<f64>, IccError> {
+    (0..count)
+        .map(|i| match bytes_per_entry {
+            1 => read_u8(data, offset + i).map(|value| value as f64 / 255.),
+            _ => read_u16(data, offset + i * 2)
+                .map(|value| value as f64 / 65535.),
+        })
+        .collect()
+}
+
+fn parse_lut(
+    data: &[u8],
+    from_pcs: bool,
+    pcs_is_lab: bool,
+) -> Result<Lut, IccError> {
+    let signature = read_signature(data, 0)?;
+    let input_channels = read_u8(data, 8)? as usize;
+    let output_channels = read_u8(data, 9)? as usize;
+    if input_channels == 0 || output_channels == 0 || input_channels > 15 {
+        return Err(IccError::Malformed("invalid lookup table channel count"));
+    }
+
+    match &signature {
+        b"mft1" | b"mft2" => {
+            let bytes_per_entry = if &signature == b"mft1" { 1 } else { 2 };
+            let grid_points = read_u8(data, 10)? as usize;
+            let matrix = read_matrix(data, 12)?;
+            // The matrix is only used when converting from XYZ.
+            let uses_matrix = from_pcs && !pcs_is_lab;
+            if uses_matrix && input_channels != 3 {
+                return Err(IccError::Malformed(
+                    "the lookup table matrix needs three channels",
+                ));
+            }
+
+            let (input_entries, output_entries, mut offset) =
+                match bytes_per_entry {
+                    1 => (256, 256, 48),
+                    _ => (
+                        read_u16(data, 48)? as usize,
+                        read_u16(data, 50)? as usize,
+                        52,
+                    ),
+                };
+
+            let read_curves =
+                |offset: &mut usize, channels: usize, entries: usize| {
+                    (0..channels)
+                        .map(|_| {
+                            let table = parse_table(
+                                data,
+                                *offset,
+                                entries,
+                                bytes_per_entry,
+                            )?;
+                            *offset += entries * bytes_per_entry;
+                            Ok(Curve::Table(table))
+                        })
+                        .collect::<Result<Vec<_>, IccError>>()
+                };
+
+            let input_curves =
+                read_curves(&mut offset, input_channels, input_entries)?;
+
+            let clut = Clut::parse(
+                data,
+                offset,
+                vec![grid_points; input_channels],
+                output_channels,
+                bytes_per_entry,
+            )?;
+            // The table was checked to fit into the data.
+            offset += clut.values.len() * bytes_per_entry;
+
+            let output_curves =
+                read_curves(&mut offset, output_channels, output_entries)?;
+
+            let pcs_encoding = match (pcs_is_lab, bytes_per_entry) {
+                (false, _) => PcsEncoding::Xyz,
+                (true, 1) => PcsEncoding::Lab,
+                (true, _) => PcsEncoding::LegacyLab,
+            };
+
+            Ok(Lut {
+                input_channels,
+                output_channels,
+                input_matrix: uses_matrix.then_some(matrix),
+                input_curves,
+                clut: Some(clut),
+                middle_curves: vec![],
+                matrix: None,
+                output_curves,
+                matrix_first: false,
+                pcs_encoding,
+            })
+        }
+        b"mAB " | b"mBA " => {
+            // Offsets are checked to be inside of the data, so that adding the
+            // small offsets within the elements can't overflow.
+            let offset_at =
+                |position: usize| -> Result<Option<usize>, IccError> {
+                    let offset = read_u32(data, position)? as usize;
+                    if offset > data.len() {
+                        return Err(IccError::UnexpectedEnd);
+                    }
+                    Ok((offset != 0).then_some(offset))
+                };
+            let b_offset = offset_at(12)?;
+            let matrix_offset = offset_at(16)?;
+            let m_offset = offset_at(20)?;
+            let clut_offset = offset_at(24)?;
+            let a_offset = offset_at(28)?;
+
+            let pcs_channels = 3;
+            let device_channels = match from_pcs {
+                true => output_channels,
+                false => input_channels,
+            };
+
+            let curves_at = |offset: Option<usize>, count: usize| match offset {
+                Some(offset) => parse_curves(data, offset, count),
+                None => Ok(vec![]),
+            };
+
+            let b_curves = curves_at(b_offset, pcs_channels)?;
+            let m_curves = curves_at(m_offset, pcs_channels)?;
+            let a_curves = curves_at(a_offset, device_channels)?;
+
+            let matrix = match matrix_offset {
+                Some(offset) => Some((
+                    read_matrix(data, offset)?,
+                    [
+                        read_s15_fixed16(data, offset + 36)?,
+                        read_s15_fixed16(data, offset + 40)?,
+                        read_s15_fixed16(data, offset + 44)?,
+                    ],
+                )),
+                None => None,
+            };
+
+            let clut = match clut_offset {
+                Some(offset) => {
+                    let (clut_inputs, clut_outputs) = match from_pcs {
+                        true => (pcs_channels, device_channels),
+                        false => (device_channels, pcs_channels),
+                    };
+                    let grid_points = (0..clut_inputs)
+                        .map(|i| read_u8(data, offset + i).map(usize::from))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let precision = read_u8(data, offset + 16)? as usize;
+                    if !(1..=2).contains(&precision) {
+                        return Err(IccError::Malformed(
+                            "unknown lookup table precision",
+                        ));
+                    }
+
+                    Some(Clut::parse(
+                        data,
+                        offset + 20,
+                        grid_points,
+                        clut_outputs,
+                        precision,
+                    )?)
+                }
+                None => None,
+            };
+
+            // The matrix always works on three channels, which in AToB are
+            // the outputs of the lookup table, if there is one.
+            let matrix_channels = match (from_pcs, &clut) {
+                (true, _) => pcs_channels,
+                (false, Some(clut)) => clut.output_channels,
+                (false, None) => device_channels,
+            };
+            if matrix.is_some() && matrix_channels != 3 {
+                return Err(IccError::Malformed(
+                    "the lookup table matrix needs three channels",
+                ));
+            }
+
+            let pcs_encoding = match pcs_is_lab {
+                true => PcsEncoding::Lab,
+                false => PcsEncoding::Xyz,
+            };
+
+            // AToB: A -> CLUT -> M -> matrix -> B
+            // BToA: B -> matrix -> M -> CLUT -> A
+            let (input_curves, output_curves) = match from_pcs {
+                true => (b_curves, a_curves),
+                false => (a_curves, b_curves),
+            };
+
+            Ok(Lut {
+                input_channels,
+                output_channels,
+                input_matrix: None,
+                input_curves,
+                clut,
+                middle_curves: m_curves,
+                matrix,
+                output_curves,
+                matrix_first: from_pcs,
+                pcs_encoding,
+            })
+        }
+        signature => Err(IccError::UnsupportedTagType(*signature)),
+    }
+}
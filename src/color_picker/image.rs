@@ -0,0 +1,165 @@
+use super::*;
+
+/// An image as 8 bit RGBA pixels, row by row, like the data of a canvas
+/// `ImageData`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbaImage {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// Returns `None` if `data` doesn't contain exactly `width * height`
+    /// pixels.
+    pub fn new(width: u32, height: u32, data: Vec<u8>) -> Option<Self> {
+        if data.len() != width as usize * height as usize * 4 {
+            return None;
+        }
+
+        Some(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The RGBA values of a pixel, or `None` if it's outside of the image.
+    pub fn rgba(&self, x: i64, y: i64) -> Option<[u8; 4]> {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return None;
+        }
+
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        let pixel = &self.data[index..index + 4];
+        Some([pixel[0], pixel[1], pixel[2], pixel[3]])
+    }
+
+    /// The color of a pixel, ignoring its alpha.
+    pub fn pixel(&self, x: i64, y: i64) -> Option<Rgb> {
+        let [r, g, b, _] = self.rgba(x, y)?;
+        Some(Rgb::from_components((r as f64, g as f64, b as f64)))
+    }
+
+    /// Iterate over the colors of all pixels which aren't fully transparent,
+    /// ignoring their alpha.
+    pub fn opaque_pixels(&self) -> impl Iterator<Item = Rgb> + '_ {
+        self.data
+            .chunks_exact(4)
+            .filter(|pixel| pixel[3] > 0)
+            .map(|pixel| {
+                Rgb::from_components((
+                    pixel[0] as f64,
+                    pixel[1] as f64,
+                    pixel[2] as f64,
+                ))
+            })
+    }
+
+    /// The average color of the `size` by `size` pixels centered on a pixel,
+    /// weighted by their alpha. Pixels outside of the image are skipped.
+    ///
+    /// Returns `None` if all of the pixels are transparent or outside of the
+    /// image.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// // A black pixel to the left of a white one.
+    /// let image =
+    ///     RgbaImage::new(2, 1, vec![0, 0, 0, 255, 255, 255, 255, 255]).unwrap();
+    ///
+    /// assert_eq!(
+    ///     image.average(1, 0, 1),
+    ///     Some(Rgb::from_components((255., 255., 255.)))
+    /// );
+    /// assert_eq!(
+    ///     image.average(1, 0, 3),
+    ///     Some(Rgb::from_components((127.5, 127.5, 127.5)))
+    /// );
+    /// assert_eq!(image.average(5, 5, 3), None);
+    /// ```
+    pub fn average(&self, x: i64, y: i64, size: u32) -> Option<Rgb> {
+        let radius = size as i64 / 2;
+
+        let mut sum = (0., 0., 0.);
+        let mut total_weight = 0.;
+        for sample_y in y - radius..=y + radius {
+            for sample_x in x - radius..=x + radius {
+                let Some([r, g, b, a]) = self.rgba(sample_x, sample_y) else {
+                    continue;
+                };
+                let weight = a as f64;
+                sum.0 += r as f64 * weight;
+                sum.1 += g as f64 * weight;
+                sum.2 += b as f64 * weight;
+                total_weight += weight;
+            }
+        }
+
+        if total_weight == 0. {
+            return None;
+        }
+
+        Some(Rgb::from_components((
+            sum.0 / total_weight,
+            sum.1 / total_weight,
+            sum.2 / total_weight,
+        )))
+    }
+
+    /// Shrink the image to at most `max_pixels` pixels, keeping its aspect
+    /// ratio. Pixels are picked instead of averaged, so no new colors are
+    /// introduced.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// let image = RgbaImage::new(400, 200, vec![0; 400 * 200 * 4]).unwrap();
+    ///
+    /// let small = image.downscale(20_000);
+    /// assert_eq!((small.width(), small.height()), (200, 100));
+    /// assert_eq!(image.downscale(1_000_000), image);
+    /// ```
+    pub fn downscale(&self, max_pixels: u32) -> RgbaImage {
+        let pixels = self.width as f64 * self.height as f64;
+        if pixels <= max_pixels as f64 {
+            return self.clone();
+        }
+
+        let scale = (max_pixels as f64 / pixels).sqrt();
+        let width = ((self.width as f64 * scale).floor() as u32).max(1);
+        let height = ((self.height as f64 * scale).floor() as u32).max(1);
+
+        let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            let source_y =
+                (y as u64 * self.height as u64 / height as u64) as i64;
+            for x in 0..width {
+                let source_x =
+                    (x as u64 * self.width as u64 / width as u64) as i64;
+                data.extend(self.rgba(source_x, source_y).unwrap_or_default());
+            }
+        }
+
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+}
@@ -0,0 +1,505 @@
+//! Interpolation between colors in the color spaces supported by CSS, following
+//! https://www.w3.org/TR/css-color-4/#interpolation.
+
+use super::*;
+
+/// A space which colors can be interpolated in, named after the CSS
+/// `<color-interpolation-method>` keywords.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InterpolationSpace {
+    Srgb,
+    LinearSrgb,
+    Lab,
+    Oklab,
+    Lch,
+    Oklch,
+}
+
+impl InterpolationSpace {
+    pub const ALL: [InterpolationSpace; 6] = [
+        InterpolationSpace::Srgb,
+        InterpolationSpace::LinearSrgb,
+        InterpolationSpace::Lab,
+        InterpolationSpace::Oklab,
+        InterpolationSpace::Lch,
+        InterpolationSpace::Oklch,
+    ];
+
+    /// The keyword used for the space in CSS.
+    pub fn css_name(&self) -> &'static str {
+        match self {
+            InterpolationSpace::Srgb => "srgb",
+            InterpolationSpace::LinearSrgb => "srgb-linear",
+            InterpolationSpace::Lab => "lab",
+            InterpolationSpace::Oklab => "oklab",
+            InterpolationSpace::Lch => "lch",
+            InterpolationSpace::Oklch => "oklch",
+        }
+    }
+
+    /// If the space has a hue angle, in which case it's stored in the last
+    /// coordinate.
+    pub fn is_polar(&self) -> bool {
+        matches!(self, InterpolationSpace::Lch | InterpolationSpace::Oklch)
+    }
+
+    /// Convert to the coordinates of the space, in the units CSS uses for
+    /// them, except that lightness and sRGB channels are in the range 0 to 1.
+    pub fn coordinates_from_rgb(&self, rgb: Rgb) -> (f64, f64, f64) {
+        let (r, g, b) = rgb.as_floats();
+        match self {
+            InterpolationSpace::Srgb => (r, g, b),
+            InterpolationSpace::LinearSrgb => {
+                (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b))
+            }
+            InterpolationSpace::Lab => {
+                let lab = Lab::from_rgb(rgb);
+                (lab.l / 100., lab.a, lab.b)
+            }
+            InterpolationSpace::Oklab => Oklab::from_rgb(rgb).as_tuple(),
+            InterpolationSpace::Lch => {
+                let lab = Lab::from_rgb(rgb);
+                let (chroma, hue) = rectangular_to_polar(lab.a, lab.b);
+                (lab.l / 100., chroma, hue)
+            }
+            InterpolationSpace::Oklch => {
+                let oklab = Oklab::from_rgb(rgb);
+                let (chroma, hue) = rectangular_to_polar(oklab.a, oklab.b);
+                (oklab.l, chroma, hue)
+            }
+        }
+    }
+
+    /// Convert coordinates back to sRGB, clipping colors outside of the gamut.
+    pub fn rgb_from_coordinates(&self, coordinates: (f64, f64, f64)) -> Rgb {
+        let (x, y, z) = coordinates;
+        match self {
+            InterpolationSpace::Srgb => Rgb::from_floats((x, y, z)),
+            InterpolationSpace::LinearSrgb => Rgb::from_floats((
+                linear_to_srgb(x.clamp(0., 1.)),
+                linear_to_srgb(y.clamp(0., 1.)),
+                linear_to_srgb(z.clamp(0., 1.)),
+            )),
+            InterpolationSpace::Lab => Lab {
+                l: x * 100.,
+                a: y,
+                b: z,
+            }
+            .as_rgb(),
+            InterpolationSpace::Oklab => {
+                Oklab::from_tuple(coordinates).as_rgb()
+            }
+            InterpolationSpace::Lch => {
+                let (a, b) = polar_to_rectangular(y, z);
+                Lab { l: x * 100., a, b }.as_rgb()
+            }
+            InterpolationSpace::Oklch => {
+                let (a, b) = polar_to_rectangular(y, z);
+                Oklab { l: x, a, b }.as_rgb()
+            }
+        }
+    }
+}
+
+impl UiDisplay for InterpolationSpace {
+    type Environment = ();
+
+    fn to_ui_string(&self, _env: Self::Environment) -> String {
+        match self {
+            InterpolationSpace::Srgb => "sRGB",
+            InterpolationSpace::LinearSrgb => "Linear sRGB",
+            InterpolationSpace::Lab => "Lab",
+            InterpolationSpace::Oklab => "OKLab",
+            InterpolationSpace::Lch => "LCh",
+            InterpolationSpace::Oklch => "OKLCh",
+        }
+        .to_owned()
+    }
+}
+
+impl Display for InterpolationSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            InterpolationSpace::Srgb => "Srgb",
+            InterpolationSpace::LinearSrgb => "LinearSrgb",
+            InterpolationSpace::Lab => "Lab",
+            InterpolationSpace::Oklab => "Oklab",
+            InterpolationSpace::Lch => "Lch",
+            InterpolationSpace::Oklch => "Oklch",
+        })
+    }
+}
+
+impl FromStr for InterpolationSpace {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Srgb" => Ok(InterpolationSpace::Srgb),
+            "LinearSrgb" => Ok(InterpolationSpace::LinearSrgb),
+            "Lab" => Ok(InterpolationSpace::Lab),
+            "Oklab" => Ok(InterpolationSpace::Oklab),
+            "Lch" => Ok(InterpolationSpace::Lch),
+            "Oklch" => Ok(InterpolationSpace::Oklch),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Which way around the hue circle to go when interpolating hues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HueInterpolation {
+    Shorter,
+    Longer,
+    Increasing,
+    Decreasing,
+}
+
+impl HueInterpolation {
+    /// The keyword used for the method in CSS.
+    pub fn css_name(&self) -> &'static str {
+        match self {
+            HueInterpolation::Shorter => "shorter",
+            HueInterpolation::Longer => "longer",
+            HueInterpolation::Increasing => "increasing",
+            HueInterpolation::Decreasing => "decreasing",
+        }
+    }
+
+    /// Adjust two hues in degrees so that interpolating linearly between them
+    /// follows this method.
+    ///
+    /// Source: https://www.w3.org/TR/css-color-4/#hue-interpolation
+    pub fn fix_up_hues(&self, hue_a: f64, hue_b: f64) -> (f64, f64) {
+        let (mut a, mut b) = (hue_a.rem_euclid(360.), hue_b.rem_euclid(360.));
+        let difference = b - a;
+
+        match self {
+            HueInterpolation::Shorter if difference > 180. => a += 360.,
+            HueInterpolation::Shorter if difference < -180. => b += 360.,
+            HueInterpolation::Longer
+                if 0. < difference && difference < 180. =>
+            {
+                a += 360.
+            }
+            HueInterpolation::Longer
+                if -180. < difference && difference <= 0. =>
+            {
+                b += 360.
+            }
+            HueInterpolation::Increasing if b < a => b += 360.,
+            HueInterpolation::Decreasing if a < b => a += 360.,
+            _ => {}
+        }
+
+        (a, b)
+    }
+}
+
+impl UiDisplay for HueInterpolation {
+    type Environment = ();
+
+    fn to_ui_string(&self, _env: Self::Environment) -> String {
+        match self {
+            HueInterpolation::Shorter => "Shorter",
+            HueInterpolation::Longer => "Longer",
+            HueInterpolation::Increasing => "Increasing",
+            HueInterpolation::Decreasing => "Decreasing",
+        }
+        .to_owned()
+    }
+}
+
+impl Display for HueInterpolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            HueInterpolation::Shorter => "Shorter",
+            HueInterpolation::Longer => "Longer",
+            HueInterpolation::Increasing => "Increasing",
+            HueInterpolation::Decreasing => "Decreasing",
+        })
+    }
+}
+
+impl FromStr for HueInterpolation {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Shorter" => Ok(HueInterpolation::Shorter),
+            "Longer" => Ok(HueInterpolation::Longer),
+            "Increasing" => Ok(HueInterpolation::Increasing),
+            "Decreasing" => Ok(HueInterpolation::Decreasing),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Format a CSS `<color-interpolation-method>`, like `in oklch longer hue`. The
+/// hue method is left out when it's the default or the space has no hue.
+pub fn interpolation_method_css(
+    space: InterpolationSpace,
+    hue_interpolation: HueInterpolation,
+) -> String {
+    match space.is_polar() && hue_interpolation != HueInterpolation::Shorter {
+        true => format!(
+            "in {} {} hue",
+            space.css_name(),
+            hue_interpolation.css_name()
+        ),
+        false => format!("in {}", space.css_name()),
+    }
+}
+
+/// Interpolate between coordinates of `space`, where `t` is 0 at `a` and 1 at
+/// `b`.
+///
+/// The hue of a color without chroma is powerless, so the other color's hue is
+/// used in its place, like CSS does.
+pub fn interpolate_coordinates(
+    a: (f64, f64, f64),
+    b: (f64, f64, f64),
+    t: f64,
+    space: InterpolationSpace,
+    hue_interpolation: HueInterpolation,
+) -> (f64, f64, f64) {
+    let (a, b) = replace_powerless_hues(a, b, space);
+
+    lerp_coordinates(a, b, t, space, hue_interpolation)
+}
+
+/// Replace the hue of a color without chroma by the hue of the other color, if
+/// `space` is polar.
+fn replace_powerless_hues(
+    a: (f64, f64, f64),
+    b: (f64, f64, f64),
+    space: InterpolationSpace,
+) -> ((f64, f64, f64), (f64, f64, f64)) {
+    const POWERLESS_CHROMA: f64 = 1e-4;
+
+    if !space.is_polar() {
+        return (a, b);
+    }
+
+    match (a.1 < POWERLESS_CHROMA, b.1 < POWERLESS_CHROMA) {
+        (true, false) => ((a.0, a.1, b.2), b),
+        (false, true) => (a, (b.0, b.1, a.2)),
+        _ => (a, b),
+    }
+}
+
+/// Interpolate between coordinates of `space` as they are, without replacing
+/// powerless hues.
+fn lerp_coordinates(
+    a: (f64, f64, f64),
+    b: (f64, f64, f64),
+    t: f64,
+    space: InterpolationSpace,
+    hue_interpolation: HueInterpolation,
+) -> (f64, f64, f64) {
+    let lerp = |a: f64, b: f64| a + (b - a) * t;
+
+    if !space.is_polar() {
+        return (lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2));
+    }
+
+    let (hue_a, hue_b) = hue_interpolation.fix_up_hues(a.2, b.2);
+
+    (
+        lerp(a.0, b.0),
+        lerp(a.1, b.1),
+        lerp(hue_a, hue_b).rem_euclid(360.),
+    )
+}
+
+/// Interpolate between two sRGB colors in `space`, where `t` is 0 at `a` and 1
+/// at `b`.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+///
+/// let red = Rgb::from_hex_code("ff0000").unwrap();
+/// let blue = Rgb::from_hex_code("0000ff").unwrap();
+///
+/// let middle = |space| {
+///     interpolate_rgb(red, blue, 0.5, space, HueInterpolation::Shorter)
+///         .as_hex_code()
+/// };
+///
+/// assert_eq!(middle(InterpolationSpace::Srgb), "800080".to_owned());
+/// assert_eq!(middle(InterpolationSpace::LinearSrgb), "bc00bc".to_owned());
+/// ```
+pub fn interpolate_rgb(
+    a: Rgb,
+    b: Rgb,
+    t: f64,
+    space: InterpolationSpace,
+    hue_interpolation: HueInterpolation,
+) -> Rgb {
+    let coordinates = interpolate_coordinates(
+        space.coordinates_from_rgb(a),
+        space.coordinates_from_rgb(b),
+        t,
+        space,
+        hue_interpolation,
+    );
+
+    space.rgb_from_coordinates(coordinates)
+}
+
+/// One of the colors passed to [color_mix], like `#ff000080 30%` in CSS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MixColor {
+    pub color: DynamicColor,
+    /// The alpha in the range 0 to 1.
+    pub alpha: f64,
+    /// The percentage in the range 0 to 100, if it was specified.
+    pub percentage: Option<f64>,
+}
+
+/// Mix two colors the way CSS `color-mix()` does, returning the color (in the
+/// color space of `a`) and its alpha.
+///
+/// The percentages are normalized so that they sum to 100, and if they
+/// originally summed to less than that the alpha of the result is scaled down
+/// accordingly. The colors are mixed with premultiplied alpha, except for their
+/// hues, which are only replaced if they are powerless in the colors as they
+/// were. Returns `None` if both percentages are zero, or if either of them is
+/// outside of the range 0 to 100.
+///
+/// Source: https://www.w3.org/TR/css-color-5/#color-mix
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+/// use approx::assert_relative_eq;
+///
+/// let red = DynamicColor::new((255., 0., 0.), ColorSpace::Rgb);
+/// let blue = DynamicColor::new((0., 0., 255.), ColorSpace::Rgb);
+///
+/// // color-mix(in srgb, red 40%, transparent blue 40%)
+/// let (color, alpha) = color_mix(
+///     MixColor { color: red, alpha: 1., percentage: Some(40.) },
+///     MixColor { color: blue, alpha: 0., percentage: Some(40.) },
+///     InterpolationSpace::Srgb,
+///     HueInterpolation::Shorter,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(color.to_color::<Rgb>().as_hex_code(), "ff0000".to_owned());
+/// assert_relative_eq!(alpha, 0.4);
+///
+/// // Negative percentages are invalid, like in CSS.
+/// let mix = |percentage_a, percentage_b| {
+///     color_mix(
+///         MixColor { color: red, alpha: 1., percentage: percentage_a },
+///         MixColor { color: blue, alpha: 1., percentage: percentage_b },
+///         InterpolationSpace::Srgb,
+///         HueInterpolation::Shorter,
+///     )
+/// };
+/// assert_eq!(mix(Some(-10.), None), None);
+/// assert_eq!(mix(None, Some(110.)), None);
+/// assert_eq!(mix(Some(0.), Some(0.)), None);
+///
+/// // The hue of a transparent color still counts, only its chroma and
+/// // lightness are weighted by its alpha.
+/// let space = InterpolationSpace::Oklch;
+/// let rose = Rgb::from_hex_code("cc6666").unwrap();
+/// let green = Rgb::from_hex_code("66aa66").unwrap();
+/// let (color, alpha) = color_mix(
+///     MixColor {
+///         color: DynamicColor::from_color(rose),
+///         alpha: 0.,
+///         percentage: None,
+///     },
+///     MixColor {
+///         color: DynamicColor::from_color(green),
+///         alpha: 1.,
+///         percentage: None,
+///     },
+///     space,
+///     HueInterpolation::Shorter,
+/// )
+/// .unwrap();
+///
+/// let rose = space.coordinates_from_rgb(rose);
+/// let green = space.coordinates_from_rgb(green);
+/// let mixed = space.coordinates_from_rgb(color.to_color());
+/// assert_relative_eq!(mixed.0, green.0, epsilon = 1e-4);
+/// assert_relative_eq!(mixed.1, green.1, epsilon = 1e-4);
+/// assert_relative_eq!(mixed.2, (rose.2 + green.2) / 2., epsilon = 1e-4);
+/// assert_relative_eq!(alpha, 0.5);
+/// ```
+pub fn color_mix(
+    a: MixColor,
+    b: MixColor,
+    space: InterpolationSpace,
+    hue_interpolation: HueInterpolation,
+) -> Option<(DynamicColor, f64)> {
+    let valid = |percentage: Option<f64>| {
+        percentage.is_none_or(|percentage| (0. ..=100.).contains(&percentage))
+    };
+    if !valid(a.percentage) || !valid(b.percentage) {
+        return None;
+    }
+
+    let (percentage_a, percentage_b) = match (a.percentage, b.percentage) {
+        (None, None) => (50., 50.),
+        (Some(percentage), None) => (percentage, 100. - percentage),
+        (None, Some(percentage)) => (100. - percentage, percentage),
+        (Some(percentage_a), Some(percentage_b)) => {
+            (percentage_a, percentage_b)
+        }
+    };
+    let sum = percentage_a + percentage_b;
+    if sum <= 0. {
+        return None;
+    }
+    let alpha_multiplier = (sum / 100.).min(1.);
+    let t = percentage_b / sum;
+
+    let premultiply = |coordinates: (f64, f64, f64), alpha: f64| {
+        let hue = match space.is_polar() {
+            true => coordinates.2,
+            false => coordinates.2 * alpha,
+        };
+        (coordinates.0 * alpha, coordinates.1 * alpha, hue)
+    };
+
+    let alpha_a = a.alpha.clamp(0., 1.);
+    let alpha_b = b.alpha.clamp(0., 1.);
+    let alpha = alpha_a + (alpha_b - alpha_a) * t;
+
+    // Premultiplying removes the chroma of transparent colors, so powerless
+    // hues are replaced before.
+    let (coordinates_a, coordinates_b) = replace_powerless_hues(
+        space.coordinates_from_rgb(a.color.to_color()),
+        space.coordinates_from_rgb(b.color.to_color()),
+        space,
+    );
+    let mixed = lerp_coordinates(
+        premultiply(coordinates_a, alpha_a),
+        premultiply(coordinates_b, alpha_b),
+        t,
+        space,
+        hue_interpolation,
+    );
+    let mixed = match alpha > 0. {
+        true => (
+            mixed.0 / alpha,
+            mixed.1 / alpha,
+            match space.is_polar() {
+                true => mixed.2,
+                false => mixed.2 / alpha,
+            },
+        ),
+        false => mixed,
+    };
+
+    let rgb = space.rgb_from_coordinates(mixed);
+
+    Some((
+        DynamicColor::from_color(rgb).set_color_space(a.color.color_space()),
+        alpha * alpha_multiplier,
+    ))
+}
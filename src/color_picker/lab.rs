@@ -0,0 +1,190 @@
+use super::*;
+
+/// A CIE L\*a\*b\* color relative to the D50 white point, which matches CSS
+/// `lab()`. L is in the range 0 to 100, while a and b are roughly in the range
+/// -128 to 127.
+///
+/// Like [Xyz] it isn't a [Color] by itself, since its components can be
+/// negative.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+/// use approx::assert_relative_eq;
+///
+/// let red = Lab::from_rgb(Rgb::from_components((255., 0., 0.)));
+///
+/// assert_relative_eq!(red.l, 54.29, epsilon = 0.05);
+/// assert_relative_eq!(red.a, 80.80, epsilon = 0.05);
+/// assert_relative_eq!(red.b, 69.89, epsilon = 0.05);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lab {
+    pub l: f64,
+    pub a: f64,
+    pub b: f64,
+}
+
+impl Lab {
+    pub fn as_tuple(&self) -> (f64, f64, f64) {
+        (self.l, self.a, self.b)
+    }
+
+    pub fn from_tuple(tuple: (f64, f64, f64)) -> Self {
+        Self {
+            l: tuple.0,
+            a: tuple.1,
+            b: tuple.2,
+        }
+    }
+
+    pub fn from_rgb(rgb: Rgb) -> Self {
+        let (r, g, b) = rgb.as_floats();
+
+        Self::from_linear_srgb((
+            srgb_to_linear(r),
+            srgb_to_linear(g),
+            srgb_to_linear(b),
+        ))
+    }
+
+    /// Convert to sRGB, clipping any channels that fall outside of the gamut.
+    pub fn as_rgb(&self) -> Rgb {
+        let (r, g, b) = self.as_linear_srgb();
+
+        Rgb::from_floats((
+            linear_to_srgb(r.clamp(0., 1.)),
+            linear_to_srgb(g.clamp(0., 1.)),
+            linear_to_srgb(b.clamp(0., 1.)),
+        ))
+    }
+
+    pub fn from_linear_srgb(linear: (f64, f64, f64)) -> Self {
+        let xyz_d65 = multiply_matrix_vector(&LINEAR_SRGB_TO_XYZ, linear);
+        let xyz_d50 = multiply_matrix_vector(
+            &chromatic_adaptation_matrix(WHITE_D65, WHITE_D50),
+            xyz_d65,
+        );
+
+        Self::from_tuple(xyz_to_lab(xyz_d50, WHITE_D50))
+    }
+
+    /// Convert to linear sRGB without clipping, which is useful for checking if
+    /// the color is inside of the sRGB gamut.
+    pub fn as_linear_srgb(&self) -> (f64, f64, f64) {
+        let xyz_d50 = lab_to_xyz(self.as_tuple(), WHITE_D50);
+        let xyz_d65 = multiply_matrix_vector(
+            &chromatic_adaptation_matrix(WHITE_D50, WHITE_D65),
+            xyz_d50,
+        );
+
+        multiply_matrix_vector(&XYZ_TO_LINEAR_SRGB, xyz_d65)
+    }
+
+    /// The CIEDE2000 color difference, where a difference of about 1 is just
+    /// noticeable.
+    ///
+    /// Source: https://hajim.rochester.edu/ece/sites/gsharma/ciede2000/
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    /// use approx::assert_relative_eq;
+    ///
+    /// // Test data from the source.
+    /// let pairs = [
+    ///     ((50., 2.6772, -79.7751), (50., 0., -82.7485), 2.0425),
+    ///     ((50., 0., 0.), (50., -1., 2.), 2.3669),
+    ///     ((50., 2.5, 0.), (73., 25., -18.), 27.1492),
+    ///     ((50., -0.001, 2.49), (50., 0.0009, -2.49), 4.8045),
+    ///     ((50., -0.001, 2.49), (50., 0.0011, -2.49), 4.7461),
+    ///     ((22.7233, 20.0904, -46.694), (23.0331, 14.973, -42.5619), 2.0373),
+    /// ];
+    /// for (a, b, difference) in pairs {
+    ///     let (a, b) = (Lab::from_tuple(a), Lab::from_tuple(b));
+    ///     assert_relative_eq!(a.delta_e(&b), difference, epsilon = 1e-4);
+    ///     assert_relative_eq!(b.delta_e(&a), difference, epsilon = 1e-4);
+    /// }
+    /// ```
+    pub fn delta_e(&self, other: &Self) -> f64 {
+        const POW_25_7: f64 = 6_103_515_625.;
+
+        let chroma_mean = (self.a.hypot(self.b) + other.a.hypot(other.b)) / 2.;
+        let g = 0.5
+            * (1.
+                - (chroma_mean.powi(7) / (chroma_mean.powi(7) + POW_25_7))
+                    .sqrt());
+
+        // The a axis is stretched to improve the difference of grays.
+        let polar = |lab: &Self| rectangular_to_polar((1. + g) * lab.a, lab.b);
+        let (c1, h1) = polar(self);
+        let (c2, h2) = polar(other);
+
+        let delta_l = other.l - self.l;
+        let delta_c = c2 - c1;
+        let delta_h = match c1 * c2 == 0. {
+            true => 0.,
+            false => match h2 - h1 {
+                delta if delta > 180. => delta - 360.,
+                delta if delta < -180. => delta + 360.,
+                delta => delta,
+            },
+        };
+        let delta_h = 2. * (c1 * c2).sqrt() * (delta_h / 2.).to_radians().sin();
+
+        let l_mean = (self.l + other.l) / 2.;
+        let c_mean = (c1 + c2) / 2.;
+        let h_mean = match c1 * c2 == 0. {
+            true => h1 + h2,
+            false if (h1 - h2).abs() <= 180. => (h1 + h2) / 2.,
+            false if h1 + h2 < 360. => (h1 + h2 + 360.) / 2.,
+            false => (h1 + h2 - 360.) / 2.,
+        };
+
+        let cos = |degrees: f64| degrees.to_radians().cos();
+        let t = 1. - 0.17 * cos(h_mean - 30.)
+            + 0.24 * cos(2. * h_mean)
+            + 0.32 * cos(3. * h_mean + 6.)
+            - 0.20 * cos(4. * h_mean - 63.);
+        let delta_theta = 30. * (-((h_mean - 275.) / 25.).powi(2)).exp();
+        let r_c = 2. * (c_mean.powi(7) / (c_mean.powi(7) + POW_25_7)).sqrt();
+        let s_l = 1.
+            + 0.015 * (l_mean - 50.).powi(2)
+                / (20. + (l_mean - 50.).powi(2)).sqrt();
+        let s_c = 1. + 0.045 * c_mean;
+        let s_h = 1. + 0.015 * c_mean * t;
+        let r_t = -(2. * delta_theta).to_radians().sin() * r_c;
+
+        let (l, c, h) = (delta_l / s_l, delta_c / s_c, delta_h / s_h);
+        (l * l + c * c + h * h + r_t * c * h).sqrt()
+    }
+}
+
+/// Convert the a and b axes of a Lab-like space into chroma and a hue in
+/// degrees. The hue of colors without chroma is 0.
+pub fn rectangular_to_polar(a: f64, b: f64) -> (f64, f64) {
+    let chroma = a.hypot(b);
+    let hue = match chroma < 1e-6 {
+        true => 0.,
+        false => b.atan2(a).to_degrees().rem_euclid(360.),
+    };
+
+    (chroma, hue)
+}
+
+/// The inverse of [rectangular_to_polar].
+pub fn polar_to_rectangular(chroma: f64, hue: f64) -> (f64, f64) {
+    let hue = hue.to_radians();
+
+    (chroma * hue.cos(), chroma * hue.sin())
+}
+
+/// Check if linear sRGB channels are inside of the gamut, allowing for some
+/// rounding error.
+pub fn linear_srgb_in_gamut(linear: (f64, f64, f64)) -> bool {
+    const TOLERANCE: f64 = 1e-5;
+
+    [linear.0, linear.1, linear.2]
+        .iter()
+        .all(|channel| (-TOLERANCE..=1. + TOLERANCE).contains(channel))
+}
@@ -0,0 +1,93 @@
+//! Color picker state encoded in URL query parameters, like
+//! `?c=ff8800&space=hsl`.
+
+use super::*;
+
+/// The state of the color picker that's shared through links. Every field is
+/// optional, so that parameters missing from a link fall back to the stored
+/// settings.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+///
+/// let link = ColorLink::from_query_pairs([
+///     ("c", "#FF8800"),
+///     ("space", "hsl"),
+///     ("precise", "1"),
+///     ("utm_source", "review"),
+/// ]);
+///
+/// assert_eq!(link.color, Rgb::from_hex_code("ff8800"));
+/// assert_eq!(link.color_space, Some(ColorSpace::Hsl));
+/// assert_eq!(link.precise_inputs, Some(true));
+/// assert_eq!(link.normalised_inputs, None);
+/// assert_eq!(link.to_query_string(), "c=ff8800&space=hsl&precise=1");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ColorLink {
+    pub color: Option<Rgb>,
+    pub color_space: Option<ColorSpace>,
+    pub precise_inputs: Option<bool>,
+    pub normalised_inputs: Option<bool>,
+}
+
+impl ColorLink {
+    /// Read the state from query parameters, ignoring unknown or invalid
+    /// ones.
+    pub fn from_query_pairs<'a>(
+        pairs: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Self {
+        let mut link = Self::default();
+
+        for (key, value) in pairs {
+            match key {
+                "c" => link.color = Rgb::from_hex_code(value),
+                "space" => link.color_space = parse_color_space(value),
+                "precise" => link.precise_inputs = parse_flag(value),
+                "normalised" => link.normalised_inputs = parse_flag(value),
+                _ => {}
+            }
+        }
+
+        link
+    }
+
+    /// Format the state as query parameters, without the leading `?`. Options
+    /// are only included when they're enabled, to keep links short.
+    pub fn to_query_string(&self) -> String {
+        let mut pairs = vec![];
+
+        if let Some(color) = self.color {
+            pairs.push(format!("c={}", color.as_hex_code()));
+        }
+        if let Some(color_space) = self.color_space {
+            pairs.push(format!(
+                "space={}",
+                color_space.to_string().to_lowercase()
+            ));
+        }
+        if self.precise_inputs == Some(true) {
+            pairs.push("precise=1".to_owned());
+        }
+        if self.normalised_inputs == Some(true) {
+            pairs.push("normalised=1".to_owned());
+        }
+
+        pairs.join("&")
+    }
+}
+
+fn parse_color_space(value: &str) -> Option<ColorSpace> {
+    ColorSpace::ALL
+        .into_iter()
+        .find(|color_space| color_space.to_string().eq_ignore_ascii_case(value))
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value {
+        "1" | "true" => Some(true),
+        "0" | "false" => Some(false),
+        _ => None,
+    }
+}
@@ -0,0 +1,129 @@
+//! Keeping a property of the color constant while the others are edited.
+
+use super::*;
+
+/// What stays constant while the color is edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LockTarget {
+    /// A component of the color space the color is edited in.
+    Component(usize),
+    /// The WCAG relative luminance, which keeps the contrast against other
+    /// colors.
+    Luminance,
+}
+
+/// A locked property, with the value it's locked to.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+/// use approx::assert_relative_eq;
+///
+/// let blue = DynamicColor::new((50., 20., 250.), ColorSpace::Oklch);
+/// let lock = ColorLock::new(LockTarget::Component(0), blue);
+///
+/// // Changing the hue keeps the OKLCh lightness, as long as the color stays
+/// // inside of the sRGB gamut.
+/// let edited = lock.apply(Hsv::from_components((200., 30., 90.)));
+/// let edited = DynamicColor::from_color(edited).to_color::<Oklch>();
+/// assert_relative_eq!(edited.l, 50., epsilon = 1e-6);
+///
+/// let lock = ColorLock::new(LockTarget::Luminance, blue);
+/// let edited = lock.apply(Hsv::from_components((120., 50., 100.)));
+/// assert_relative_eq!(
+///     relative_luminance(edited.as_rgb()),
+///     relative_luminance(blue.to_color()),
+///     epsilon = 1e-6,
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorLock {
+    target: LockTarget,
+    color_space: ColorSpace,
+    value: f64,
+}
+
+impl ColorLock {
+    /// Lock the property to its value in `color`. Components are locked in
+    /// the color space of `color`.
+    pub fn new(target: LockTarget, color: DynamicColor) -> Self {
+        let value = match target {
+            LockTarget::Component(index) => {
+                let (a, b, c) = color.components();
+                [a, b, c][index.min(2)]
+            }
+            LockTarget::Luminance => relative_luminance(color.to_color()),
+        };
+
+        Self {
+            target,
+            color_space: color.color_space(),
+            value,
+        }
+    }
+
+    pub fn target(&self) -> LockTarget {
+        self.target
+    }
+
+    /// Change an edited color so that the locked property has its locked
+    /// value again.
+    ///
+    /// The luminance is reached by changing the HSV value, keeping the hue
+    /// and saturation. If it can't be reached, the closest value is used.
+    pub fn apply(&self, hsv: Hsv) -> Hsv {
+        match self.target {
+            LockTarget::Component(index) => {
+                let color = DynamicColor::from_color(hsv)
+                    .set_color_space(self.color_space);
+                let (a, b, c) = color.components();
+                let mut components = [a, b, c];
+                components[index.min(2)] = self.value;
+                let [a, b, c] = components;
+
+                let locked = DynamicColor::new((a, b, c), self.color_space)
+                    .to_color::<Hsv>();
+                keep_hue(hsv, locked)
+            }
+            LockTarget::Luminance => {
+                let (hue, sat, _) = hsv.as_floats();
+                let luminance = |value: f64| {
+                    relative_luminance(
+                        Hsv::from_floats((hue, sat, value)).as_rgb(),
+                    )
+                };
+
+                // The luminance only increases with the value.
+                let (mut low, mut high) = (0., 1.);
+                for _ in 0..40 {
+                    let middle = (low + high) / 2.;
+                    if luminance(middle) < self.value {
+                        low = middle;
+                    } else {
+                        high = middle;
+                    }
+                }
+                Hsv::from_floats((hue, sat, (low + high) / 2.))
+            }
+        }
+    }
+}
+
+/// Grays and black don't have a hue or saturation after being converted, so
+/// they're taken from the original color instead.
+fn keep_hue(original: Hsv, converted: Hsv) -> Hsv {
+    if converted.v == 0. {
+        Hsv {
+            h: original.h,
+            s: original.s,
+            v: 0.,
+        }
+    } else if converted.s == 0. {
+        Hsv {
+            h: original.h,
+            ..converted
+        }
+    } else {
+        converted
+    }
+}
@@ -1,4 +1,45 @@
+mod aces;
+mod adjustment;
 pub mod components;
+mod contrast;
+mod geometry;
+mod harmony;
+mod history;
+mod icc;
+mod image;
+mod interpolation;
+mod lab;
+mod link;
+mod lock;
+mod munsell;
+mod oklab;
+mod palette;
+mod palette_formats;
+mod quantize;
+mod recolor;
+mod scale;
+mod xyz;
+
+pub use aces::*;
+pub use adjustment::*;
+pub use contrast::*;
+pub use geometry::*;
+pub use harmony::*;
+pub use history::*;
+pub use icc::*;
+pub use image::*;
+pub use interpolation::*;
+pub use lab::*;
+pub use link::*;
+pub use lock::*;
+pub use munsell::*;
+pub use oklab::*;
+pub use palette::*;
+pub use palette_formats::*;
+pub use quantize::*;
+pub use recolor::*;
+pub use scale::*;
+pub use xyz::*;
 
 use std::{fmt::Display, str::FromStr};
 
@@ -12,6 +53,9 @@ pub struct ColorSpaceInfo {
         Option<&'static str>,
         Option<&'static str>,
     ),
+    /// How many decimals components should be displayed with, in addition to
+    /// the ones added by the "Precise Inputs" option.
+    pub component_decimals: usize,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -19,24 +63,74 @@ pub enum ColorSpace {
     Rgb,
     Hsl,
     Hsv,
-    // TODO: Figure out what color spaces to add
+    AcesCg,
+    Aces2065,
+    Oklch,
 }
 
 impl ColorSpace {
+    pub const ALL: [ColorSpace; 6] = [
+        ColorSpace::Rgb,
+        ColorSpace::Hsl,
+        ColorSpace::Hsv,
+        ColorSpace::AcesCg,
+        ColorSpace::Aces2065,
+        ColorSpace::Oklch,
+    ];
+
     pub fn info(&self) -> ColorSpaceInfo {
         match self {
             ColorSpace::Rgb => ColorSpaceInfo {
                 labels: ("R", "G", "B"),
                 units: (None, None, None),
+                component_decimals: 0,
             },
             ColorSpace::Hsl => ColorSpaceInfo {
                 labels: ("H", "S", "L"),
                 units: (Some("°"), Some("%"), Some("%")),
+                component_decimals: 0,
             },
             ColorSpace::Hsv => ColorSpaceInfo {
                 labels: ("H", "S", "V"),
                 units: (Some("°"), Some("%"), Some("%")),
+                component_decimals: 0,
+            },
+            ColorSpace::AcesCg | ColorSpace::Aces2065 => ColorSpaceInfo {
+                labels: ("R", "G", "B"),
+                units: (None, None, None),
+                component_decimals: 4,
             },
+            ColorSpace::Oklch => ColorSpaceInfo {
+                labels: ("L", "C", "H"),
+                units: (Some("%"), Some("%"), Some("°")),
+                component_decimals: 1,
+            },
+        }
+    }
+
+    /// Which component holds the hue angle in degrees, if the space has one.
+    pub fn hue_component(&self) -> Option<usize> {
+        match self {
+            ColorSpace::Hsl | ColorSpace::Hsv => Some(0),
+            ColorSpace::Oklch => Some(2),
+            ColorSpace::Rgb | ColorSpace::AcesCg | ColorSpace::Aces2065 => None,
+        }
+    }
+
+    /// Which component holds the chroma or saturation, if the space has a hue.
+    pub fn chroma_component(&self) -> Option<usize> {
+        match self {
+            ColorSpace::Hsl | ColorSpace::Hsv | ColorSpace::Oklch => Some(1),
+            ColorSpace::Rgb | ColorSpace::AcesCg | ColorSpace::Aces2065 => None,
+        }
+    }
+
+    /// Which component holds the lightness or value, if the space has a hue.
+    pub fn lightness_component(&self) -> Option<usize> {
+        match self {
+            ColorSpace::Hsl | ColorSpace::Hsv => Some(2),
+            ColorSpace::Oklch => Some(0),
+            ColorSpace::Rgb | ColorSpace::AcesCg | ColorSpace::Aces2065 => None,
         }
     }
 
@@ -45,6 +139,9 @@ impl ColorSpace {
             ColorSpace::Rgb => Rgb::COMPONENT_MAXES,
             ColorSpace::Hsl => Hsl::COMPONENT_MAXES,
             ColorSpace::Hsv => Hsv::COMPONENT_MAXES,
+            ColorSpace::AcesCg => AcesCg::COMPONENT_MAXES,
+            ColorSpace::Aces2065 => Aces2065::COMPONENT_MAXES,
+            ColorSpace::Oklch => Oklch::COMPONENT_MAXES,
         }
     }
 
@@ -56,6 +153,9 @@ impl ColorSpace {
             ColorSpace::Rgb => Rgb::clamp_components,
             ColorSpace::Hsl => Hsl::clamp_components,
             ColorSpace::Hsv => Hsv::clamp_components,
+            ColorSpace::AcesCg => AcesCg::clamp_components,
+            ColorSpace::Aces2065 => Aces2065::clamp_components,
+            ColorSpace::Oklch => Oklch::clamp_components,
         };
 
         clamp(components)
@@ -69,6 +169,9 @@ impl ColorSpace {
             ColorSpace::Rgb => Rgb::components_to_floats,
             ColorSpace::Hsl => Hsl::components_to_floats,
             ColorSpace::Hsv => Hsv::components_to_floats,
+            ColorSpace::AcesCg => AcesCg::components_to_floats,
+            ColorSpace::Aces2065 => Aces2065::components_to_floats,
+            ColorSpace::Oklch => Oklch::components_to_floats,
         };
 
         convert(components)
@@ -79,6 +182,9 @@ impl ColorSpace {
             ColorSpace::Rgb => Rgb::floats_to_components,
             ColorSpace::Hsl => Hsl::floats_to_components,
             ColorSpace::Hsv => Hsv::floats_to_components,
+            ColorSpace::AcesCg => AcesCg::floats_to_components,
+            ColorSpace::Aces2065 => Aces2065::floats_to_components,
+            ColorSpace::Oklch => Oklch::floats_to_components,
         };
 
         convert(floats)
@@ -89,6 +195,9 @@ impl ColorSpace {
             ColorSpace::Rgb => Rgb::from_rgb(rgb).as_components(),
             ColorSpace::Hsl => Hsl::from_rgb(rgb).as_components(),
             ColorSpace::Hsv => Hsv::from_rgb(rgb).as_components(),
+            ColorSpace::AcesCg => AcesCg::from_rgb(rgb).as_components(),
+            ColorSpace::Aces2065 => Aces2065::from_rgb(rgb).as_components(),
+            ColorSpace::Oklch => Oklch::from_rgb(rgb).as_components(),
         }
     }
     fn rgb_from_color_components(&self, components: (f64, f64, f64)) -> Rgb {
@@ -96,6 +205,11 @@ impl ColorSpace {
             ColorSpace::Rgb => Rgb::from_components(components).as_rgb(),
             ColorSpace::Hsl => Hsl::from_components(components).as_rgb(),
             ColorSpace::Hsv => Hsv::from_components(components).as_rgb(),
+            ColorSpace::AcesCg => AcesCg::from_components(components).as_rgb(),
+            ColorSpace::Aces2065 => {
+                Aces2065::from_components(components).as_rgb()
+            }
+            ColorSpace::Oklch => Oklch::from_components(components).as_rgb(),
         }
     }
 }
@@ -108,6 +222,9 @@ impl UiDisplay for ColorSpace {
             ColorSpace::Rgb => "RGB",
             ColorSpace::Hsl => "HSL",
             ColorSpace::Hsv => "HSV",
+            ColorSpace::AcesCg => "ACEScg",
+            ColorSpace::Aces2065 => "ACES2065-1",
+            ColorSpace::Oklch => "OKLCh",
         }
         .to_owned()
     }
@@ -119,6 +236,9 @@ impl Display for ColorSpace {
             ColorSpace::Rgb => "Rgb",
             ColorSpace::Hsl => "Hsl",
             ColorSpace::Hsv => "Hsv",
+            ColorSpace::AcesCg => "AcesCg",
+            ColorSpace::Aces2065 => "Aces2065",
+            ColorSpace::Oklch => "Oklch",
         })
     }
 }
@@ -130,6 +250,9 @@ impl FromStr for ColorSpace {
             "Rgb" => Ok(ColorSpace::Rgb),
             "Hsl" => Ok(ColorSpace::Hsl),
             "Hsv" => Ok(ColorSpace::Hsv),
+            "AcesCg" => Ok(ColorSpace::AcesCg),
+            "Aces2065" => Ok(ColorSpace::Aces2065),
+            "Oklch" => Ok(ColorSpace::Oklch),
             _ => Err(()),
         }
     }
@@ -223,10 +346,171 @@ impl DynamicColor {
         self.color_space.components_to_floats(self.components)
     }
 
+    /// Mix in `amount` of another opaque color, where 0 is only this color and
+    /// 1 is only `other`, like CSS `color-mix()`. The color keeps its current
+    /// color space.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// let red = DynamicColor::new((255., 0., 0.), ColorSpace::Rgb);
+    /// let blue = DynamicColor::new((240., 100., 50.), ColorSpace::Hsl);
+    ///
+    /// let purple = red.mix(
+    ///     blue,
+    ///     0.5,
+    ///     InterpolationSpace::Srgb,
+    ///     HueInterpolation::Shorter,
+    /// );
+    ///
+    /// assert_eq!(purple.color_space(), ColorSpace::Rgb);
+    /// assert_eq!(purple.to_color::<Rgb>().as_hex_code(), "800080".to_owned());
+    /// ```
+    pub fn mix(
+        self,
+        other: DynamicColor,
+        amount: f64,
+        space: InterpolationSpace,
+        hue_interpolation: HueInterpolation,
+    ) -> Self {
+        let rgb = interpolate_rgb(
+            self.to_color(),
+            other.to_color(),
+            amount.clamp(0., 1.),
+            space,
+            hue_interpolation,
+        );
+
+        DynamicColor::from_color(rgb).set_color_space(self.color_space)
+    }
+
+    /// Rotate the hue by some degrees, as measured in `hue_space`. The color
+    /// keeps its current color space.
+    ///
+    /// Returns `None` if `hue_space` doesn't have a hue component.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// let red = DynamicColor::new((255., 0., 0.), ColorSpace::Rgb);
+    /// let cyan = red.rotate_hue(180., ColorSpace::Hsv).unwrap();
+    ///
+    /// assert_eq!(cyan.color_space(), ColorSpace::Rgb);
+    /// assert_eq!(cyan.to_color::<Rgb>().as_hex_code(), "00ffff".to_owned());
+    ///
+    /// assert_eq!(red.rotate_hue(180., ColorSpace::Rgb), None);
+    /// ```
+    pub fn rotate_hue(
+        self,
+        degrees: f64,
+        hue_space: ColorSpace,
+    ) -> Option<Self> {
+        let index = hue_space.hue_component()?;
+        let color_space = self.color_space;
+
+        let mut components = self.set_color_space(hue_space).components;
+        let hue = match index {
+            0 => &mut components.0,
+            1 => &mut components.1,
+            _ => &mut components.2,
+        };
+        *hue = (*hue + degrees).rem_euclid(360.);
+
+        Some(
+            DynamicColor::new(components, hue_space)
+                .set_color_space(color_space),
+        )
+    }
+
     pub fn set_floats(mut self, floats: (f64, f64, f64)) -> Self {
         self.components = self.color_space.floats_to_components(floats);
         self
     }
+
+    /// The float of the component at `index`, in the range 0 to 1.
+    pub fn float(&self, index: usize) -> f64 {
+        let floats = self.as_floats();
+        match index {
+            0 => floats.0,
+            1 => floats.1,
+            _ => floats.2,
+        }
+    }
+
+    /// Set the float of the component at `index`.
+    pub fn set_float(self, index: usize, float: f64) -> Self {
+        match index {
+            0 => self.set_float_0(float),
+            1 => self.set_float_1(float),
+            _ => self.set_float_2(float),
+        }
+    }
+
+    /// The colors reached by varying only the component at `index` from 0 to
+    /// its max, in `steps` evenly spaced steps, like the track of a slider for
+    /// that component.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// let color = DynamicColor::new((0., 128., 255.), ColorSpace::Rgb);
+    ///
+    /// let gradient = color
+    ///     .component_gradient(0, 3)
+    ///     .into_iter()
+    ///     .map(Rgb::as_hex_code)
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(gradient, vec!["0080ff", "8080ff", "ff80ff"]);
+    /// ```
+    pub fn component_gradient(&self, index: usize, steps: usize) -> Vec<Rgb> {
+        (0..steps)
+            .map(|step| {
+                let float = step as f64 / (steps.max(2) - 1) as f64;
+                self.set_float(index, float).to_color::<Rgb>()
+            })
+            .collect()
+    }
+}
+
+/// A compact representation of the color space and exact components, like
+/// `Hsl:210:50:40`, which is safe to use as a cookie value.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+///
+/// let color = DynamicColor::new((210., 50., 40.5), ColorSpace::Hsl);
+///
+/// assert_eq!(color.to_string(), "Hsl:210:50:40.5".to_owned());
+/// assert_eq!("Hsl:210:50:40.5".parse(), Ok(color));
+/// assert_eq!("Hsl:210:50".parse::<DynamicColor>(), Err(()));
+/// ```
+impl Display for DynamicColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (a, b, c) = self.components;
+        write!(f, "{}:{}:{}:{}", self.color_space, a, b, c)
+    }
+}
+
+impl FromStr for DynamicColor {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let color_space = parts.next().ok_or(())?.parse::<ColorSpace>()?;
+        let mut component = || -> Result<f64, ()> {
+            parts.next().ok_or(())?.parse::<f64>().map_err(|_| ())
+        };
+        let components = (component()?, component()?, component()?);
+        if parts.next().is_some() {
+            return Err(());
+        }
+
+        Ok(Self::new(components, color_space))
+    }
 }
 
 pub trait Color {
@@ -333,13 +617,85 @@ impl Rgb {
     /// assert_eq!(Rgb::from_components((0., 255., 0.)).as_hex_code(), "00ff00".to_owned());
     /// ```
     pub fn as_hex_code(self) -> String {
-        let r = self.r as u8;
-        let g = self.g as u8;
-        let b = self.b as u8;
+        let (r, g, b) = self.as_bytes();
 
         format!("{:02x}{:02x}{:02x}", r, g, b)
     }
 
+    /// Round the components to bytes, the way they are shown everywhere.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// let rgb = Rgb::from_components((127.5, 0.4, 255.));
+    ///
+    /// assert_eq!(rgb.as_bytes(), (128, 0, 255));
+    /// assert_eq!(rgb.as_css_variables(), "--r: 128; --g: 0; --b: 255;");
+    /// ```
+    pub fn as_bytes(self) -> (u8, u8, u8) {
+        let byte = |component: f64| component.round().clamp(0., 255.) as u8;
+
+        (byte(self.r), byte(self.g), byte(self.b))
+    }
+
+    /// The `--r`, `--g` and `--b` CSS variables used to style swatches.
+    pub fn as_css_variables(self) -> String {
+        let (r, g, b) = self.as_bytes();
+
+        format!("--r: {}; --g: {}; --b: {};", r, g, b)
+    }
+
+    /// Format rgb color and an alpha in the range 0 to 1 as an hex code, where
+    /// the alpha is only included if it isn't opaque.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// let red = Rgb::from_components((255., 0., 0.));
+    ///
+    /// assert_eq!(red.as_hex_code_with_alpha(1.), "ff0000".to_owned());
+    /// assert_eq!(red.as_hex_code_with_alpha(0.5), "ff000080".to_owned());
+    /// ```
+    pub fn as_hex_code_with_alpha(self, alpha: f64) -> String {
+        let alpha = (alpha.clamp(0., 1.) * 255.).round() as u8;
+
+        match alpha {
+            255 => self.as_hex_code(),
+            _ => format!("{}{:02x}", self.as_hex_code(), alpha),
+        }
+    }
+
+    /// Create a rgb color and an alpha in the range 0 to 1 from a hex code with
+    /// either six or eight digits.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// let red = Rgb::from_components((255., 0., 0.));
+    ///
+    /// assert_eq!(Rgb::from_hex_code_with_alpha("#ff0000"), Some((red, 1.)));
+    /// assert_eq!(Rgb::from_hex_code_with_alpha("ff000000"), Some((red, 0.)));
+    /// assert_eq!(Rgb::from_hex_code_with_alpha("ff00000"), None);
+    /// ```
+    pub fn from_hex_code_with_alpha(code: &str) -> Option<(Self, f64)> {
+        let code = code.trim_start_matches('#');
+
+        match code.len() {
+            6 => Some((Self::from_hex_code(code)?, 1.)),
+            8 => {
+                let alpha = u8::from_str_radix(code.get(6..)?, 16).ok()?;
+                Some((
+                    Self::from_hex_code(code.get(..6)?)?,
+                    alpha as f64 / 255.,
+                ))
+            }
+            _ => None,
+        }
+    }
+
     /// Create a rgb color from a hex code.
     ///
     /// The code may begin with a hashtag.
@@ -0,0 +1,403 @@
+//! Conversion between sRGB and Munsell hue, value and chroma notation, based on
+//! the Munsell renotation data embedded in `munsell_renotation.dat`.
+
+use super::*;
+use std::sync::OnceLock;
+
+/// The chromaticity of Illuminant C, which the renotation data is relative to.
+const ILLUMINANT_C_XY: (f64, f64) = (0.31006, 0.31616);
+/// The XYZ coordinates of Illuminant C, with `Y = 1`.
+const WHITE_C: (f64, f64, f64) = (0.98074, 1., 1.18232);
+
+/// Colors closer than this to the chromaticity of the illuminant are treated as
+/// neutral.
+const NEUTRAL_TOLERANCE: f64 = 0.002;
+
+const HUE_FAMILIES: [&str; 10] =
+    ["R", "YR", "Y", "GY", "G", "BG", "B", "PB", "P", "RP"];
+
+/// A color in Munsell notation, like `5R 4/14`.
+///
+/// The hue is stored as a number in the range 0 to 100, where every ten steps
+/// make up one hue family, starting with `R`. That means that `5R` is 5, `5YR`
+/// is 15, and `10RP` is 100 (or 0).
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+///
+/// let color: Munsell = "5R 4/14".parse().unwrap();
+/// assert_eq!(color, Munsell { hue: 5., value: 4., chroma: 14. });
+/// assert_eq!(color.to_string(), "5R 4/14".to_owned());
+///
+/// let gray: Munsell = "N 6.5/".parse().unwrap();
+/// assert_eq!(gray.to_string(), "N 6.5/".to_owned());
+///
+/// assert_eq!("10XX 4/14".parse::<Munsell>(), Err(()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Munsell {
+    pub hue: f64,
+    pub value: f64,
+    pub chroma: f64,
+}
+
+impl Munsell {
+    pub fn is_neutral(&self) -> bool {
+        self.chroma <= 0.
+    }
+
+    /// Find the Munsell notation closest to an sRGB color.
+    ///
+    /// Returns `None` if the color falls outside of the renotation data.
+    pub fn from_rgb(rgb: Rgb) -> Option<Self> {
+        let xyz = multiply_matrix_vector(
+            &chromatic_adaptation_matrix(WHITE_D65, WHITE_C),
+            Xyz::from_rgb(rgb).as_tuple(),
+        );
+        let sum = xyz.0 + xyz.1 + xyz.2;
+        let value = luminance_to_munsell_value(xyz.1 * 100.);
+
+        if sum <= f64::EPSILON || value <= 0. {
+            return Some(Self::neutral(0.));
+        }
+        let xy = (xyz.0 / sum, xyz.1 / sum);
+
+        let (lower, upper, t) = bracket_values(value)?;
+        let lower = nearest_hue_chroma(lower, xy)?;
+        let upper = nearest_hue_chroma(upper, xy)?;
+
+        let chroma = lower.1 * (1. - t) + upper.1 * t;
+        let hue = match (lower.0, upper.0) {
+            (Some(lower), Some(upper)) => {
+                circular_mean(&[(lower, 1. - t), (upper, t)], 100.)
+            }
+            (Some(hue), None) | (None, Some(hue)) => hue,
+            (None, None) => return Some(Self::neutral(value)),
+        };
+
+        Some(Self { hue, value, chroma })
+    }
+
+    /// Convert the notation to sRGB, clipping colors outside of its gamut.
+    ///
+    /// Returns `None` if the color falls outside of the renotation data.
+    pub fn to_rgb(&self) -> Option<Rgb> {
+        let luminance = munsell_value_to_luminance(self.value) / 100.;
+
+        let xy = match self.is_neutral() {
+            true => ILLUMINANT_C_XY,
+            false => {
+                let (lower, upper, t) = bracket_values(self.value)?;
+                let lower = interpolate_xy(lower, self.hue, self.chroma)?;
+                let upper = interpolate_xy(upper, self.hue, self.chroma)?;
+                (
+                    lower.0 * (1. - t) + upper.0 * t,
+                    lower.1 * (1. - t) + upper.1 * t,
+                )
+            }
+        };
+
+        if xy.1 <= 0. {
+            return None;
+        }
+        let xyz_c = (
+            xy.0 * luminance / xy.1,
+            luminance,
+            (1. - xy.0 - xy.1) * luminance / xy.1,
+        );
+        let xyz = multiply_matrix_vector(
+            &chromatic_adaptation_matrix(WHITE_C, WHITE_D65),
+            xyz_c,
+        );
+
+        Some(Xyz::from_tuple(xyz).as_rgb())
+    }
+
+    fn neutral(value: f64) -> Self {
+        Self {
+            hue: 0.,
+            value,
+            chroma: 0.,
+        }
+    }
+}
+
+impl Display for Munsell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = naturally_format_float(self.value, 0, 1);
+        if self.is_neutral() {
+            return write!(f, "N {}/", value);
+        }
+
+        // Hues are written in the range (0, 10] within their family, so 0R is
+        // written as 10RP.
+        let hue = self.hue.round_digits(-1).rem_euclid(100.);
+        let family_index = ((hue / 10.).ceil() as usize + 9) % 10;
+        let step = match hue - family_index as f64 * 10. {
+            step if step <= 0. => 10.,
+            step => step,
+        };
+
+        write!(
+            f,
+            "{}{} {}/{}",
+            naturally_format_float(step, 0, 1),
+            HUE_FAMILIES[family_index],
+            value,
+            naturally_format_float(self.chroma, 0, 1)
+        )
+    }
+}
+
+impl FromStr for Munsell {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (hue, rest) = s.trim().split_once(' ').ok_or(())?;
+        let (value, chroma) = rest.trim().split_once('/').ok_or(())?;
+        let value = value.trim().parse::<f64>().map_err(|_| ())?;
+        if !(0. ..=10.).contains(&value) {
+            return Err(());
+        }
+
+        if hue == "N" {
+            return match chroma.trim() {
+                "" | "0" => Ok(Self::neutral(value)),
+                _ => Err(()),
+            };
+        }
+
+        let hue = parse_hue(hue).ok_or(())?;
+        let chroma = chroma.trim().parse::<f64>().map_err(|_| ())?;
+        if chroma < 0. {
+            return Err(());
+        }
+
+        Ok(Self { hue, value, chroma })
+    }
+}
+
+/// Parse a hue like `5R` or `2.5YR` into the range 0 to 100.
+fn parse_hue(hue: &str) -> Option<f64> {
+    let split = hue.find(|char: char| char.is_ascii_alphabetic())?;
+    let (step, family) = hue.split_at(split);
+
+    let step = step.parse::<f64>().ok()?;
+    if !(0. ..=10.).contains(&step) {
+        return None;
+    }
+    let family_index = HUE_FAMILIES.iter().position(|name| *name == family)?;
+
+    Some(family_index as f64 * 10. + step)
+}
+
+/// Convert a Munsell value to its luminance in the range 0 to 100, using the
+/// polynomial of ASTM D1535.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+/// use approx::assert_relative_eq;
+///
+/// assert_relative_eq!(munsell_value_to_luminance(5.), 19.27, epsilon = 0.01);
+/// assert_relative_eq!(luminance_to_munsell_value(19.27), 5., epsilon = 0.01);
+/// ```
+pub fn munsell_value_to_luminance(value: f64) -> f64 {
+    let v = value;
+    1.1914 * v - 0.22533 * v.powi(2) + 0.23352 * v.powi(3)
+        - 0.020484 * v.powi(4)
+        + 0.00081939 * v.powi(5)
+}
+
+/// The inverse of [munsell_value_to_luminance], clamped to the range 0 to 10.
+pub fn luminance_to_munsell_value(luminance: f64) -> f64 {
+    let (mut low, mut high) = (0., 10.);
+    for _ in 0..40 {
+        let middle = (low + high) / 2.;
+        if munsell_value_to_luminance(middle) < luminance {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / 2.
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RenotationSample {
+    /// `None` for neutral samples.
+    hue: Option<f64>,
+    value: f64,
+    chroma: f64,
+    x: f64,
+    y: f64,
+}
+
+fn renotation_data() -> &'static [RenotationSample] {
+    static DATA: OnceLock<Vec<RenotationSample>> = OnceLock::new();
+
+    DATA.get_or_init(|| {
+        include_str!("munsell_renotation.dat")
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut columns = line.split_whitespace();
+                let hue = match columns.next()? {
+                    "N" => None,
+                    hue => Some(parse_hue(hue)?),
+                };
+                let mut number = || columns.next()?.parse::<f64>().ok();
+
+                Some(RenotationSample {
+                    hue,
+                    value: number()?,
+                    chroma: number()?,
+                    x: number()?,
+                    y: number()?,
+                })
+            })
+            .collect()
+    })
+}
+
+/// Find the two values in the data surrounding `value`, and how far between
+/// them it is.
+fn bracket_values(value: f64) -> Option<(f64, f64, f64)> {
+    let mut values = renotation_data()
+        .iter()
+        .map(|sample| sample.value)
+        .collect::<Vec<_>>();
+    values.sort_by(f64::total_cmp);
+    values.dedup();
+
+    let upper_index = values.iter().position(|upper| *upper >= value)?;
+    let upper = values[upper_index];
+    let lower = match upper_index {
+        0 => return Some((upper, upper, 0.)),
+        index => values[index - 1],
+    };
+
+    Some((lower, upper, (value - lower) / (upper - lower)))
+}
+
+/// Estimate the hue and chroma of a chromaticity within one value of the
+/// data, using inverse distance weighting of the nearest samples.
+///
+/// The hue is `None` for neutral colors.
+fn nearest_hue_chroma(
+    value: f64,
+    xy: (f64, f64),
+) -> Option<(Option<f64>, f64)> {
+    const NEIGHBORS: usize = 4;
+
+    let distance = |a: (f64, f64), b: (f64, f64)| {
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+    };
+
+    if distance(xy, ILLUMINANT_C_XY) < NEUTRAL_TOLERANCE {
+        return Some((None, 0.));
+    }
+
+    let mut samples = renotation_data()
+        .iter()
+        .filter(|sample| sample.value == value && sample.hue.is_some())
+        .map(|sample| (distance(xy, (sample.x, sample.y)), sample))
+        .collect::<Vec<_>>();
+    samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+    samples.truncate(NEIGHBORS);
+
+    if samples.is_empty() {
+        return None;
+    }
+    if let Some((_, sample)) = samples
+        .iter()
+        .find(|(distance, _)| *distance < f64::EPSILON)
+    {
+        return Some((sample.hue, sample.chroma));
+    }
+
+    let weights = samples
+        .iter()
+        .map(|(distance, sample)| (1. / distance, sample))
+        .collect::<Vec<_>>();
+    let total = weights.iter().map(|(weight, _)| weight).sum::<f64>();
+
+    let chroma = weights
+        .iter()
+        .map(|(weight, sample)| weight * sample.chroma)
+        .sum::<f64>()
+        / total;
+    let hue = circular_mean(
+        &weights
+            .iter()
+            .filter_map(|(weight, sample)| Some((sample.hue?, *weight)))
+            .collect::<Vec<_>>(),
+        100.,
+    );
+
+    Some((Some(hue), chroma))
+}
+
+/// Interpolate the chromaticity of a hue and chroma within one value of the
+/// data, between the surrounding samples of the hue and chroma grid.
+fn interpolate_xy(value: f64, hue: f64, chroma: f64) -> Option<(f64, f64)> {
+    const HUE_STEP: f64 = 2.5;
+    const CHROMA_STEP: f64 = 2.;
+
+    let hue = hue.rem_euclid(100.);
+    let hue_lower = (hue / HUE_STEP).floor() * HUE_STEP;
+    let hue_t = (hue - hue_lower) / HUE_STEP;
+    let chroma_lower = (chroma / CHROMA_STEP).floor() * CHROMA_STEP;
+    let chroma_t = (chroma - chroma_lower) / CHROMA_STEP;
+
+    let sample = |hue: f64, chroma: f64| -> Option<(f64, f64)> {
+        if chroma <= 0. {
+            return Some(ILLUMINANT_C_XY);
+        }
+        let hue = hue.rem_euclid(100.);
+        renotation_data()
+            .iter()
+            .find(|sample| {
+                sample.value == value
+                    && sample.chroma == chroma
+                    && sample.hue.is_some_and(|sample_hue| {
+                        sample_hue
+                            .rem_euclid(100.)
+                            .float_compare_digits(hue, -3)
+                    })
+            })
+            .map(|sample| (sample.x, sample.y))
+    };
+
+    let lerp = |a: (f64, f64), b: (f64, f64), t: f64| {
+        (a.0 * (1. - t) + b.0 * t, a.1 * (1. - t) + b.1 * t)
+    };
+
+    let chroma_upper = chroma_lower + CHROMA_STEP;
+    let lower_hue = lerp(
+        sample(hue_lower, chroma_lower)?,
+        sample(hue_lower, chroma_upper)?,
+        chroma_t,
+    );
+    let upper_hue = lerp(
+        sample(hue_lower + HUE_STEP, chroma_lower)?,
+        sample(hue_lower + HUE_STEP, chroma_upper)?,
+        chroma_t,
+    );
+
+    Some(lerp(lower_hue, upper_hue, hue_t))
+}
+
+/// The weighted mean of angles in the range 0 to `period`.
+fn circular_mean(angles: &[(f64, f64)], period: f64) -> f64 {
+    let to_radians = std::f64::consts::TAU / period;
+    let (sin, cos) =
+        angles.iter().fold((0., 0.), |(sin, cos), (angle, weight)| {
+            (
+                sin + (angle * to_radians).sin() * weight,
+                cos + (angle * to_radians).cos() * weight,
+            )
+        });
+
+    (sin.atan2(cos) / to_radians).rem_euclid(period)
+}
@@ -0,0 +1,174 @@
+use super::*;
+
+/// Source: https://bottosson.github.io/posts/oklab/
+const LINEAR_SRGB_TO_LMS: Matrix3 = [
+    [0.4122214708, 0.5363325363, 0.0514459929],
+    [0.2119034982, 0.6806995451, 0.1073969566],
+    [0.0883024619, 0.2817188376, 0.6299787005],
+];
+/// Source: https://bottosson.github.io/posts/oklab/
+const LMS_TO_OKLAB: Matrix3 = [
+    [0.2104542553, 0.7936177850, -0.0040720468],
+    [1.9779984951, -2.4285922050, 0.4505937099],
+    [0.0259040371, 0.7827717662, -0.8086757660],
+];
+/// Source: https://bottosson.github.io/posts/oklab/
+const OKLAB_TO_LMS: Matrix3 = [
+    [1., 0.3963377774, 0.2158037573],
+    [1., -0.1055613458, -0.0638541728],
+    [1., -0.0894841775, -1.2914855480],
+];
+/// Source: https://bottosson.github.io/posts/oklab/
+const LMS_TO_LINEAR_SRGB: Matrix3 = [
+    [4.0767416621, -3.3077115913, 0.2309699292],
+    [-1.2684380046, 2.6097574011, -0.3413193965],
+    [-0.0041960863, -0.7034186147, 1.7076147010],
+];
+
+/// The chroma which CSS maps to 100% in `oklch()`.
+pub const OKLCH_CHROMA_MAX: f64 = 0.4;
+
+/// A color in the perceptually uniform Oklab space, with `l` in the range 0 to
+/// 1, and `a` and `b` roughly in the range -0.4 to 0.4.
+///
+/// Like [Xyz] it isn't a [Color] by itself, since its components can be
+/// negative. Use [Oklch] for a color space with the same perceptual
+/// properties.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklab {
+    pub l: f64,
+    pub a: f64,
+    pub b: f64,
+}
+
+impl Oklab {
+    pub fn as_tuple(&self) -> (f64, f64, f64) {
+        (self.l, self.a, self.b)
+    }
+
+    pub fn from_tuple(tuple: (f64, f64, f64)) -> Self {
+        Self {
+            l: tuple.0,
+            a: tuple.1,
+            b: tuple.2,
+        }
+    }
+
+    pub fn from_rgb(rgb: Rgb) -> Self {
+        let (r, g, b) = rgb.as_floats();
+
+        Self::from_linear_srgb((
+            srgb_to_linear(r),
+            srgb_to_linear(g),
+            srgb_to_linear(b),
+        ))
+    }
+
+    /// Convert to sRGB, clipping any channels that fall outside of the gamut.
+    pub fn as_rgb(&self) -> Rgb {
+        let (r, g, b) = self.as_linear_srgb();
+
+        Rgb::from_floats((
+            linear_to_srgb(r.clamp(0., 1.)),
+            linear_to_srgb(g.clamp(0., 1.)),
+            linear_to_srgb(b.clamp(0., 1.)),
+        ))
+    }
+
+    pub fn from_linear_srgb(linear: (f64, f64, f64)) -> Self {
+        let (l, m, s) = multiply_matrix_vector(&LINEAR_SRGB_TO_LMS, linear);
+
+        Self::from_tuple(multiply_matrix_vector(
+            &LMS_TO_OKLAB,
+            (l.cbrt(), m.cbrt(), s.cbrt()),
+        ))
+    }
+
+    /// Convert to linear sRGB without clipping, which is useful for checking if
+    /// the color is inside of the sRGB gamut.
+    pub fn as_linear_srgb(&self) -> (f64, f64, f64) {
+        let (l, m, s) = multiply_matrix_vector(&OKLAB_TO_LMS, self.as_tuple());
+
+        multiply_matrix_vector(
+            &LMS_TO_LINEAR_SRGB,
+            (l.powi(3), m.powi(3), s.powi(3)),
+        )
+    }
+}
+
+/// The cylindrical form of [Oklab], with the lightness and chroma stored as
+/// percentages like CSS `oklch()` does, where a chroma of 100% is
+/// [OKLCH_CHROMA_MAX].
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+/// use approx::assert_relative_eq;
+///
+/// let red = Oklch::from_rgb(Rgb::from_components((255., 0., 0.)));
+///
+/// assert_relative_eq!(red.l, 62.80, epsilon = 0.01);
+/// assert_relative_eq!(red.c, 0.2577 / OKLCH_CHROMA_MAX * 100., epsilon = 0.01);
+/// assert_relative_eq!(red.h, 29.23, epsilon = 0.01);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklch {
+    pub l: f64,
+    pub c: f64,
+    pub h: f64,
+}
+
+impl Oklch {
+    pub fn as_oklab(&self) -> Oklab {
+        let (a, b) =
+            polar_to_rectangular(self.c / 100. * OKLCH_CHROMA_MAX, self.h);
+
+        Oklab {
+            l: self.l / 100.,
+            a,
+            b,
+        }
+    }
+
+    pub fn from_oklab(oklab: Oklab) -> Self {
+        let (chroma, hue) = rectangular_to_polar(oklab.a, oklab.b);
+
+        Self::from_components((
+            oklab.l * 100.,
+            chroma / OKLCH_CHROMA_MAX * 100.,
+            hue,
+        ))
+    }
+}
+
+impl Color for Oklch {
+    const COMPONENT_MAXES: (f64, f64, f64) = (100., 100., 360.);
+    const COLOR_SPACE: ColorSpace = ColorSpace::Oklch;
+
+    fn as_components(&self) -> (f64, f64, f64) {
+        (self.l, self.c, self.h)
+    }
+    fn from_components(components: (f64, f64, f64)) -> Self {
+        let components = Self::clamp_components(components);
+
+        Self {
+            l: components.0,
+            c: components.1,
+            h: components.2,
+        }
+    }
+
+    fn as_floats(&self) -> (f64, f64, f64) {
+        Self::components_to_floats(self.as_components())
+    }
+    fn from_floats(floats: (f64, f64, f64)) -> Self {
+        Self::from_components(Self::floats_to_components(floats))
+    }
+
+    fn as_rgb(&self) -> Rgb {
+        self.as_oklab().as_rgb()
+    }
+    fn from_rgb(rgb: Rgb) -> Self {
+        Self::from_oklab(Oklab::from_rgb(rgb))
+    }
+}
@@ -0,0 +1,176 @@
+//! Named palettes of saved colors, stored in local storage as JSON.
+
+use super::*;
+use serde::{Deserialize, Serialize};
+
+/// The local storage key the palette library is stored under.
+pub const PALETTE_LIBRARY_KEY: &str = "palette_library";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Swatch {
+    /// Used to identify the swatch while it's being edited. It isn't stored.
+    #[serde(skip, default = "unique_id")]
+    pub id: u64,
+    #[serde(with = "hex_code")]
+    pub color: Rgb,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl Swatch {
+    pub fn new(color: Rgb) -> Self {
+        Self {
+            id: unique_id(),
+            color,
+            name: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Palette {
+    /// Used to identify the palette while it's being edited. It isn't stored.
+    #[serde(skip, default = "unique_id")]
+    pub id: u64,
+    pub name: String,
+    pub swatches: Vec<Swatch>,
+}
+
+impl Palette {
+    pub fn new(name: String) -> Self {
+        Self {
+            id: unique_id(),
+            name,
+            swatches: vec![],
+        }
+    }
+
+    pub fn add_color(&mut self, color: Rgb) {
+        self.swatches.push(Swatch::new(color));
+    }
+
+    pub fn remove_swatch(&mut self, id: u64) {
+        self.swatches.retain(|swatch| swatch.id != id);
+    }
+
+    /// Move a swatch so that it ends up at `index`, shifting the swatches in
+    /// between.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// let mut palette = Palette::new("Grays".to_owned());
+    /// for value in [0., 127., 255.] {
+    ///     palette.add_color(Rgb::from_components((value, value, value)));
+    /// }
+    /// let black = palette.swatches[0].id;
+    ///
+    /// palette.move_swatch(black, 2);
+    ///
+    /// let hex_codes = palette
+    ///     .swatches
+    ///     .iter()
+    ///     .map(|swatch| swatch.color.as_hex_code())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(hex_codes, vec!["7f7f7f", "ffffff", "000000"]);
+    /// ```
+    pub fn move_swatch(&mut self, id: u64, index: usize) {
+        let Some(from) =
+            self.swatches.iter().position(|swatch| swatch.id == id)
+        else {
+            return;
+        };
+
+        let swatch = self.swatches.remove(from);
+        let index = index.min(self.swatches.len());
+        self.swatches.insert(index, swatch);
+    }
+}
+
+/// All of the user's palettes, in order.
+///
+/// It's converted to and from JSON through [Display] and [FromStr], so that it
+/// can be stored with [crate::settings::local_storage_set_value].
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+///
+/// let library: PaletteLibrary = r##"{"palettes": [
+///     {"name": "Brand", "swatches": [{"color": "#ff8800"}]}
+/// ]}"##
+///     .parse()
+///     .unwrap();
+///
+/// assert_eq!(library.palettes[0].name, "Brand".to_owned());
+/// assert_eq!(
+///     library.palettes[0].swatches[0].color,
+///     Rgb::from_components((255., 136., 0.))
+/// );
+/// assert_eq!(
+///     library.to_string(),
+///     r##"{"palettes":[{"name":"Brand","swatches":[{"color":"#ff8800"}]}]}"##
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PaletteLibrary {
+    pub palettes: Vec<Palette>,
+}
+
+impl PaletteLibrary {
+    /// Add an empty palette, returning its id.
+    pub fn add_palette(&mut self, name: String) -> u64 {
+        let palette = Palette::new(name);
+        let id = palette.id;
+        self.palettes.push(palette);
+        id
+    }
+
+    pub fn remove_palette(&mut self, id: u64) {
+        self.palettes.retain(|palette| palette.id != id);
+    }
+
+    pub fn palette(&self, id: u64) -> Option<&Palette> {
+        self.palettes.iter().find(|palette| palette.id == id)
+    }
+
+    pub fn palette_mut(&mut self, id: u64) -> Option<&mut Palette> {
+        self.palettes.iter_mut().find(|palette| palette.id == id)
+    }
+}
+
+impl Display for PaletteLibrary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| std::fmt::Error)?;
+        f.write_str(&json)
+    }
+}
+
+impl FromStr for PaletteLibrary {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s).map_err(|_| ())
+    }
+}
+
+/// Serializes colors as `#rrggbb` hex codes.
+mod hex_code {
+    use super::*;
+    use serde::{de::Error, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        color: &Rgb,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("#{}", color.as_hex_code()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Rgb, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Rgb::from_hex_code(&code)
+            .ok_or_else(|| D::Error::custom("invalid hex code"))
+    }
+}
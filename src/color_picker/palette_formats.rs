@@ -0,0 +1,709 @@
+//! Reading and writing palettes in the file formats of other tools.
+//!
+//! Sources:
+//! - GIMP: https://developer.gimp.org/core/standards/gpl/
+//! - Adobe Swatch Exchange: http://www.selapa.net/swatches/colors/fileformats.php#adobe_ase
+//! - Photoshop: https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#50577411_pgfId-1055819
+//! - Paint.NET: https://www.getpaint.net/doc/latest/WorkingWithPalettes.html
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaletteFormatError {
+    /// The data ended before the structure being read did.
+    UnexpectedEnd,
+    /// The file doesn't start like the format requires.
+    InvalidHeader,
+    InvalidUtf8,
+    /// The file was read, but didn't contain any colors.
+    NoColors,
+    Malformed(&'static str),
+    /// The palette doesn't fit into the format, like too many colors for the
+    /// 16 bit count of `.aco` files.
+    TooLarge(&'static str),
+}
+
+impl Display for PaletteFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaletteFormatError::UnexpectedEnd => {
+                write!(f, "the file is truncated")
+            }
+            PaletteFormatError::InvalidHeader => {
+                write!(f, "the file isn't in the expected format")
+            }
+            PaletteFormatError::InvalidUtf8 => {
+                write!(f, "the file isn't valid UTF-8 text")
+            }
+            PaletteFormatError::NoColors => {
+                write!(f, "the file doesn't contain any colors")
+            }
+            PaletteFormatError::Malformed(reason) => {
+                write!(f, "the file is malformed: {}", reason)
+            }
+            PaletteFormatError::TooLarge(reason) => {
+                write!(f, "the palette doesn't fit into the format: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PaletteFormatError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PaletteFormat {
+    /// GIMP `.gpl`.
+    Gpl,
+    /// Adobe Swatch Exchange `.ase`.
+    Ase,
+    /// Photoshop color swatches `.aco`.
+    Aco,
+    /// Paint.NET palette text `.txt`.
+    PaintNet,
+    /// CSS custom properties.
+    Css,
+    /// The same JSON that palettes are stored as.
+    Json,
+}
+
+impl PaletteFormat {
+    pub const ALL: [PaletteFormat; 6] = [
+        PaletteFormat::Gpl,
+        PaletteFormat::Ase,
+        PaletteFormat::Aco,
+        PaletteFormat::PaintNet,
+        PaletteFormat::Css,
+        PaletteFormat::Json,
+    ];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PaletteFormat::Gpl => "gpl",
+            PaletteFormat::Ase => "ase",
+            PaletteFormat::Aco => "aco",
+            PaletteFormat::PaintNet => "txt",
+            PaletteFormat::Css => "css",
+            PaletteFormat::Json => "json",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            PaletteFormat::Gpl | PaletteFormat::PaintNet => "text/plain",
+            PaletteFormat::Ase | PaletteFormat::Aco => {
+                "application/octet-stream"
+            }
+            PaletteFormat::Css => "text/css",
+            PaletteFormat::Json => "application/json",
+        }
+    }
+
+    /// Guess the format of a file from its extension, falling back to looking
+    /// at its contents.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// assert_eq!(
+    ///     PaletteFormat::detect("brand.ASE", b""),
+    ///     PaletteFormat::Ase
+    /// );
+    /// assert_eq!(
+    ///     PaletteFormat::detect("brand", b"GIMP Palette\n"),
+    ///     PaletteFormat::Gpl
+    /// );
+    /// assert_eq!(
+    ///     PaletteFormat::detect("brand.txt", b"; paint.net Palette File\n"),
+    ///     PaletteFormat::PaintNet
+    /// );
+    /// ```
+    pub fn detect(file_name: &str, bytes: &[u8]) -> Self {
+        let extension = file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_lowercase());
+        match extension.as_deref() {
+            Some("gpl") => return PaletteFormat::Gpl,
+            Some("ase") => return PaletteFormat::Ase,
+            Some("aco") => return PaletteFormat::Aco,
+            Some("css") | Some("scss") => return PaletteFormat::Css,
+            Some("json") => return PaletteFormat::Json,
+            _ => {}
+        }
+
+        let text = String::from_utf8_lossy(bytes);
+        let text = text.trim_start();
+        match () {
+            _ if bytes.starts_with(b"ASEF") => PaletteFormat::Ase,
+            _ if bytes.starts_with(&[0, 1]) || bytes.starts_with(&[0, 2]) => {
+                PaletteFormat::Aco
+            }
+            _ if text.starts_with("GIMP Palette") => PaletteFormat::Gpl,
+            _ if text.starts_with('{') => PaletteFormat::Json,
+            _ if text.contains("--") => PaletteFormat::Css,
+            _ => PaletteFormat::PaintNet,
+        }
+    }
+
+    /// Read a palette. `fallback_name` is used as its name if the file doesn't
+    /// contain one.
+    pub fn read(
+        &self,
+        bytes: &[u8],
+        fallback_name: &str,
+    ) -> Result<Palette, PaletteFormatError> {
+        let mut palette = match self {
+            PaletteFormat::Gpl => read_gpl(as_text(bytes)?)?,
+            PaletteFormat::Ase => read_ase(bytes)?,
+            PaletteFormat::Aco => read_aco(bytes)?,
+            PaletteFormat::PaintNet => read_paint_net(as_text(bytes)?)?,
+            PaletteFormat::Css => read_css(as_text(bytes)?)?,
+            PaletteFormat::Json => serde_json::from_str(as_text(bytes)?)
+                .map_err(|_| PaletteFormatError::Malformed("invalid JSON"))?,
+        };
+
+        if palette.swatches.is_empty() {
+            return Err(PaletteFormatError::NoColors);
+        }
+        if palette.name.trim().is_empty() {
+            palette.name = fallback_name.to_owned();
+        }
+
+        Ok(palette)
+    }
+
+    /// Write a palette, failing if it doesn't fit into the format.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// let mut palette = Palette::new("Brand".to_owned());
+    /// palette.add_color(Rgb::from_components((255., 136., 0.)));
+    ///
+    /// for format in PaletteFormat::ALL {
+    ///     let read = format.read(&format.write(&palette).unwrap(), "").unwrap();
+    ///     assert_eq!(read.swatches[0].color.as_hex_code(), "ff8800");
+    /// }
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(PaletteFormat::Gpl.write(&palette).unwrap())
+    ///         .unwrap(),
+    ///     "GIMP Palette\nName: Brand\nColumns: 0\n#\n255 136   0\tUntitled\n"
+    /// );
+    ///
+    /// // The color count of `.aco` files is a 16 bit number.
+    /// for _ in 0..u16::MAX {
+    ///     palette.add_color(Rgb::from_components((0., 0., 0.)));
+    /// }
+    /// assert!(matches!(
+    ///     PaletteFormat::Aco.write(&palette),
+    ///     Err(PaletteFormatError::TooLarge(_))
+    /// ));
+    /// ```
+    pub fn write(
+        &self,
+        palette: &Palette,
+    ) -> Result<Vec<u8>, PaletteFormatError> {
+        Ok(match self {
+            PaletteFormat::Gpl => write_gpl(palette).into_bytes(),
+            PaletteFormat::Ase => write_ase(palette)?,
+            PaletteFormat::Aco => write_aco(palette)?,
+            PaletteFormat::PaintNet => write_paint_net(palette).into_bytes(),
+            PaletteFormat::Css => write_css(palette).into_bytes(),
+            PaletteFormat::Json => serde_json::to_string_pretty(palette)
+                .unwrap_or_default()
+                .into_bytes(),
+        })
+    }
+}
+
+impl UiDisplay for PaletteFormat {
+    type Environment = ();
+
+    fn to_ui_string(&self, _env: Self::Environment) -> String {
+        match self {
+            PaletteFormat::Gpl => "GIMP (.gpl)",
+            PaletteFormat::Ase => "Adobe Swatch Exchange (.ase)",
+            PaletteFormat::Aco => "Photoshop (.aco)",
+            PaletteFormat::PaintNet => "Paint.NET (.txt)",
+            PaletteFormat::Css => "CSS (.css)",
+            PaletteFormat::Json => "JSON (.json)",
+        }
+        .to_owned()
+    }
+}
+
+impl Display for PaletteFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PaletteFormat::Gpl => "Gpl",
+            PaletteFormat::Ase => "Ase",
+            PaletteFormat::Aco => "Aco",
+            PaletteFormat::PaintNet => "PaintNet",
+            PaletteFormat::Css => "Css",
+            PaletteFormat::Json => "Json",
+        })
+    }
+}
+
+impl FromStr for PaletteFormat {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Gpl" => Ok(PaletteFormat::Gpl),
+            "Ase" => Ok(PaletteFormat::Ase),
+            "Aco" => Ok(PaletteFormat::Aco),
+            "PaintNet" => Ok(PaletteFormat::PaintNet),
+            "Css" => Ok(PaletteFormat::Css),
+            "Json" => Ok(PaletteFormat::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+const UNTITLED_SWATCH: &str = "Untitled";
+
+fn as_text(bytes: &[u8]) -> Result<&str, PaletteFormatError> {
+    std::str::from_utf8(bytes).map_err(|_| PaletteFormatError::InvalidUtf8)
+}
+
+fn named_swatch(color: Rgb, name: &str) -> Swatch {
+    let name = name.trim();
+    Swatch {
+        name: (!name.is_empty() && name != UNTITLED_SWATCH)
+            .then(|| name.to_owned()),
+        ..Swatch::new(color)
+    }
+}
+
+fn swatch_name(swatch: &Swatch) -> &str {
+    swatch.name.as_deref().unwrap_or(UNTITLED_SWATCH)
+}
+
+fn read_gpl(text: &str) -> Result<Palette, PaletteFormatError> {
+    let mut lines = text.lines();
+    if lines.next().map(str::trim) != Some("GIMP Palette") {
+        return Err(PaletteFormatError::InvalidHeader);
+    }
+
+    let mut palette = Palette::new("".to_owned());
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix("Name:") {
+            palette.name = name.trim().to_owned();
+            continue;
+        }
+        if line.starts_with("Columns:") {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let mut channel = || -> Result<f64, PaletteFormatError> {
+            parts
+                .next()
+                .and_then(|part| part.parse::<u8>().ok())
+                .map(|value| value as f64)
+                .ok_or(PaletteFormatError::Malformed("invalid color line"))
+        };
+        let color = Rgb::from_components((channel()?, channel()?, channel()?));
+        let name = parts.collect::<Vec<_>>().join(" ");
+
+        palette.swatches.push(named_swatch(color, &name));
+    }
+
+    Ok(palette)
+}
+
+fn write_gpl(palette: &Palette) -> String {
+    let colors = palette
+        .swatches
+        .iter()
+        .map(|swatch| {
+            let (r, g, b) = swatch.color.as_bytes();
+            format!("{:3} {:3} {:3}\t{}\n", r, g, b, swatch_name(swatch))
+        })
+        .collect::<String>();
+
+    format!(
+        "GIMP Palette\nName: {}\nColumns: 0\n#\n{}",
+        palette.name, colors
+    )
+}
+
+fn read_paint_net(text: &str) -> Result<Palette, PaletteFormatError> {
+    let mut palette = Palette::new("".to_owned());
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix(';') {
+            if let Some(name) = comment.trim().strip_prefix("Name:") {
+                palette.name = name.trim().to_owned();
+            }
+            continue;
+        }
+
+        // Colors are written as AARRGGBB, and the alpha is ignored.
+        let code = match line.len() {
+            8 => line.get(2..),
+            6 => Some(line),
+            _ => None,
+        };
+        let color = code
+            .and_then(Rgb::from_hex_code)
+            .ok_or(PaletteFormatError::Malformed("invalid color line"))?;
+        palette.swatches.push(Swatch::new(color));
+    }
+
+    Ok(palette)
+}
+
+fn write_paint_net(palette: &Palette) -> String {
+    let colors = palette
+        .swatches
+        .iter()
+        .map(|swatch| {
+            format!("FF{}\n", swatch.color.as_hex_code().to_uppercase())
+        })
+        .collect::<String>();
+
+    format!(
+        "; paint.net Palette File\n; Name: {}\n{}",
+        palette.name, colors
+    )
+}
+
+/// Reads every custom property with a hex code value, like
+/// `--brand-orange: #ff8800;`.
+fn read_css(text: &str) -> Result<Palette, PaletteFormatError> {
+    let mut palette = Palette::new("".to_owned());
+
+    // Exported palettes start with a comment containing the name.
+    if let Some((name, _)) = text
+        .trim_start()
+        .strip_prefix("/*")
+        .and_then(|text| text.split_once("*/"))
+    {
+        palette.name = name.trim().to_owned();
+    }
+
+    for declaration in text.split([';', '{', '}', '\n']) {
+        let Some((property, value)) = declaration.split_once(':') else {
+            continue;
+        };
+        let Some(name) = property.trim().strip_prefix("--") else {
+            continue;
+        };
+        let Some(color) = Rgb::from_hex_code(value.trim()) else {
+            continue;
+        };
+
+        palette.swatches.push(named_swatch(color, name));
+    }
+
+    Ok(palette)
+}
+
+fn write_css(palette: &Palette) -> String {
+    let prefix = css_identifier(&palette.name, "color");
+    let properties = palette
+        .swatches
+        .iter()
+        .enumerate()
+        .map(|(index, swatch)| {
+            let name = match &swatch.name {
+                Some(name) => css_identifier(name, ""),
+                None => "".to_owned(),
+            };
+            let name = match name.is_empty() {
+                true => format!("{}-{}", prefix, index + 1),
+                false => name,
+            };
+            format!("  --{}: #{};\n", name, swatch.color.as_hex_code())
+        })
+        .collect::<String>();
+
+    format!("/* {} */\n:root {{\n{}}}\n", palette.name, properties)
+}
+
+/// Turn a name into a lowercase, kebab-case CSS identifier.
+fn css_identifier(name: &str, fallback: &str) -> String {
+    let identifier = name
+        .split(|char: char| !char.is_ascii_alphanumeric() && char != '_')
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+
+    match identifier.is_empty() {
+        true => fallback.to_owned(),
+        false => identifier,
+    }
+}
+
+/// Reads big endian values from the start of a byte slice.
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], PaletteFormatError> {
+        if self.bytes.len() < length {
+            return Err(PaletteFormatError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u16(&mut self) -> Result<u16, PaletteFormatError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, PaletteFormatError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, PaletteFormatError> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+
+    /// Read `length` UTF-16 code units, dropping the null terminator.
+    fn utf16(&mut self, length: usize) -> Result<String, PaletteFormatError> {
+        let units = (0..length)
+            .map(|_| self.u16())
+            .collect::<Result<Vec<_>, _>>()?;
+        let units = match units.split_last() {
+            Some((0, units)) => units,
+            _ => &units,
+        };
+        Ok(String::from_utf16_lossy(units))
+    }
+}
+
+fn write_utf16(output: &mut Vec<u8>, text: &str) {
+    for unit in text.encode_utf16().chain([0]) {
+        output.extend(unit.to_be_bytes());
+    }
+}
+
+fn cmyk_to_rgb(cyan: f64, magenta: f64, yellow: f64, black: f64) -> Rgb {
+    Rgb::from_floats((
+        (1. - cyan) * (1. - black),
+        (1. - magenta) * (1. - black),
+        (1. - yellow) * (1. - black),
+    ))
+}
+
+fn read_ase(bytes: &[u8]) -> Result<Palette, PaletteFormatError> {
+    let mut reader = ByteReader { bytes };
+    if reader.take(4)? != b"ASEF" {
+        return Err(PaletteFormatError::InvalidHeader);
+    }
+    let _version = reader.take(4)?;
+    let block_count = reader.u32()?;
+
+    let mut palette = Palette::new("".to_owned());
+    for _ in 0..block_count {
+        let block_type = reader.u16()?;
+        let length = reader.u32()? as usize;
+        let mut block = ByteReader {
+            bytes: reader.take(length)?,
+        };
+
+        match block_type {
+            // The first group names the palette.
+            0xc001 if palette.name.is_empty() => {
+                let name_length = block.u16()? as usize;
+                palette.name = block.utf16(name_length)?;
+            }
+            0x0001 => {
+                let name_length = block.u16()? as usize;
+                let name = block.utf16(name_length)?;
+                let model = block.take(4)?;
+
+                let color = match model {
+                    b"RGB " => Rgb::from_floats((
+                        block.f32()? as f64,
+                        block.f32()? as f64,
+                        block.f32()? as f64,
+                    )),
+                    b"CMYK" => cmyk_to_rgb(
+                        block.f32()? as f64,
+                        block.f32()? as f64,
+                        block.f32()? as f64,
+                        block.f32()? as f64,
+                    ),
+                    b"LAB " => Lab {
+                        l: block.f32()? as f64 * 100.,
+                        a: block.f32()? as f64,
+                        b: block.f32()? as f64,
+                    }
+                    .as_rgb(),
+                    b"Gray" => {
+                        let value = block.f32()? as f64;
+                        Rgb::from_floats((value, value, value))
+                    }
+                    _ => {
+                        return Err(PaletteFormatError::Malformed(
+                            "unknown color model",
+                        ))
+                    }
+                };
+
+                palette.swatches.push(named_swatch(color, &name));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(palette)
+}
+
+fn write_ase(palette: &Palette) -> Result<Vec<u8>, PaletteFormatError> {
+    let mut blocks = vec![];
+
+    let mut group = vec![];
+    write_utf16_block_name(&mut group, &palette.name)?;
+    blocks.push((0xc001u16, group));
+
+    for swatch in &palette.swatches {
+        let mut block = vec![];
+        write_utf16_block_name(&mut block, swatch_name(swatch))?;
+        block.extend(b"RGB ");
+        let (r, g, b) = swatch.color.as_floats();
+        for channel in [r, g, b] {
+            block.extend((channel as f32).to_be_bytes());
+        }
+        // Normal, as opposed to global or spot, colors.
+        block.extend(2u16.to_be_bytes());
+        blocks.push((0x0001, block));
+    }
+    blocks.push((0xc002, vec![]));
+
+    let mut output = b"ASEF".to_vec();
+    output.extend(1u16.to_be_bytes());
+    output.extend(0u16.to_be_bytes());
+    output.extend((blocks.len() as u32).to_be_bytes());
+    for (block_type, block) in blocks {
+        output.extend(block_type.to_be_bytes());
+        output.extend((block.len() as u32).to_be_bytes());
+        output.extend(block);
+    }
+
+    Ok(output)
+}
+
+fn write_utf16_block_name(
+    output: &mut Vec<u8>,
+    name: &str,
+) -> Result<(), PaletteFormatError> {
+    output.extend(utf16_length(name)?.to_be_bytes());
+    write_utf16(output, name);
+    Ok(())
+}
+
+/// The 16 bit length of a null terminated UTF-16 string, as stored before it.
+fn utf16_length(text: &str) -> Result<u16, PaletteFormatError> {
+    u16::try_from(text.encode_utf16().count() + 1)
+        .map_err(|_| PaletteFormatError::TooLarge("a name is too long"))
+}
+
+fn read_aco(bytes: &[u8]) -> Result<Palette, PaletteFormatError> {
+    let mut reader = ByteReader { bytes };
+    let mut palette = Palette::new("".to_owned());
+
+    // Version 1 only contains colors, and is optionally followed by version 2
+    // which repeats them with names.
+    while !reader.is_empty() {
+        let version = reader.u16()?;
+        if version != 1 && version != 2 {
+            return Err(PaletteFormatError::InvalidHeader);
+        }
+        let count = reader.u16()?;
+
+        let mut swatches = vec![];
+        for _ in 0..count {
+            let space = reader.u16()?;
+            let values =
+                [reader.u16()?, reader.u16()?, reader.u16()?, reader.u16()?];
+            let name = match version {
+                2 => {
+                    let _ = reader.u16()?;
+                    let length = reader.u16()? as usize;
+                    reader.utf16(length)?
+                }
+                _ => "".to_owned(),
+            };
+
+            let [w, x, y, z] = values.map(|value| value as f64 / 65535.);
+            let color = match space {
+                0 => Rgb::from_floats((w, x, y)),
+                1 => Hsv::from_floats((w, x, y)).as_rgb(),
+                // Inks are stored inverted, so 0 means full coverage.
+                2 => cmyk_to_rgb(1. - w, 1. - x, 1. - y, 1. - z),
+                7 => Lab {
+                    l: values[0] as f64 / 100.,
+                    a: values[1] as i16 as f64 / 100.,
+                    b: values[2] as i16 as f64 / 100.,
+                }
+                .as_rgb(),
+                8 => {
+                    let value = values[0] as f64 / 10000.;
+                    Rgb::from_floats((value, value, value))
+                }
+                _ => {
+                    return Err(PaletteFormatError::Malformed(
+                        "unknown color space",
+                    ))
+                }
+            };
+            swatches.push(named_swatch(color, &name));
+        }
+
+        // The named version 2 swatches replace the version 1 ones.
+        palette.swatches = swatches;
+    }
+
+    Ok(palette)
+}
+
+fn write_aco(palette: &Palette) -> Result<Vec<u8>, PaletteFormatError> {
+    let count = u16::try_from(palette.swatches.len())
+        .map_err(|_| PaletteFormatError::TooLarge("too many colors"))?;
+
+    let mut output = vec![];
+    for version in [1u16, 2] {
+        output.extend(version.to_be_bytes());
+        output.extend(count.to_be_bytes());
+
+        for swatch in &palette.swatches {
+            let (r, g, b) = swatch.color.as_floats();
+            output.extend(0u16.to_be_bytes());
+            for channel in [r, g, b, 0.] {
+                output
+                    .extend(((channel * 65535.).round() as u16).to_be_bytes());
+            }
+            if version == 2 {
+                let name = swatch_name(swatch);
+                output.extend(0u16.to_be_bytes());
+                output.extend(utf16_length(name)?.to_be_bytes());
+                write_utf16(&mut output, name);
+            }
+        }
+    }
+
+    Ok(output)
+}
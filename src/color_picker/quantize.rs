@@ -0,0 +1,426 @@
+//! Finding the dominant colors of an image.
+//!
+//! Sources:
+//! - Median cut: Heckbert, P. (1982). Color Image Quantization for Frame Buffer
+//!   Display.
+//! - k-means: https://en.wikipedia.org/wiki/K-means_clustering
+
+use super::*;
+
+/// At most this many pixels are used, evenly spread over the image, to keep
+/// large images fast.
+const MAX_SAMPLES: usize = 65536;
+
+/// k-means stops after this many iterations, even if it hasn't converged.
+const K_MEANS_ITERATIONS: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DominantColor {
+    pub color: Rgb,
+    /// The share of the pixels which belong to the color, in the range 0 to
+    /// 1.
+    pub proportion: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuantizeMethod {
+    /// Recursively split the sRGB color cube at the median.
+    MedianCut,
+    /// k-means clustering in CIE Lab, starting from the median cut colors.
+    KMeansLab,
+    /// k-means clustering in OKLab, starting from the median cut colors.
+    KMeansOklab,
+}
+
+impl QuantizeMethod {
+    pub const ALL: [QuantizeMethod; 3] = [
+        QuantizeMethod::MedianCut,
+        QuantizeMethod::KMeansLab,
+        QuantizeMethod::KMeansOklab,
+    ];
+
+    /// Reduce the pixels to at most `count` colors, ordered by how many
+    /// pixels belong to them.
+    pub fn quantize(&self, pixels: &[Rgb], count: usize) -> Vec<DominantColor> {
+        match self {
+            QuantizeMethod::MedianCut => median_cut(pixels, count),
+            QuantizeMethod::KMeansLab => {
+                k_means(pixels, count, ClusterSpace::Lab)
+            }
+            QuantizeMethod::KMeansOklab => {
+                k_means(pixels, count, ClusterSpace::Oklab)
+            }
+        }
+    }
+}
+
+impl UiDisplay for QuantizeMethod {
+    type Environment = ();
+
+    fn to_ui_string(&self, _env: Self::Environment) -> String {
+        match self {
+            QuantizeMethod::MedianCut => "Median Cut",
+            QuantizeMethod::KMeansLab => "k-means (Lab)",
+            QuantizeMethod::KMeansOklab => "k-means (OKLab)",
+        }
+        .to_owned()
+    }
+}
+
+impl Display for QuantizeMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            QuantizeMethod::MedianCut => "MedianCut",
+            QuantizeMethod::KMeansLab => "KMeansLab",
+            QuantizeMethod::KMeansOklab => "KMeansOklab",
+        })
+    }
+}
+
+impl FromStr for QuantizeMethod {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "MedianCut" => Ok(QuantizeMethod::MedianCut),
+            "KMeansLab" => Ok(QuantizeMethod::KMeansLab),
+            "KMeansOklab" => Ok(QuantizeMethod::KMeansOklab),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The color space k-means measures distances in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClusterSpace {
+    Lab,
+    Oklab,
+}
+
+impl ClusterSpace {
+    fn coordinates(&self, rgb: Rgb) -> [f64; 3] {
+        let (x, y, z) = match self {
+            ClusterSpace::Lab => Lab::from_rgb(rgb).as_tuple(),
+            ClusterSpace::Oklab => Oklab::from_rgb(rgb).as_tuple(),
+        };
+        [x, y, z]
+    }
+
+    fn rgb(&self, [x, y, z]: [f64; 3]) -> Rgb {
+        match self {
+            ClusterSpace::Lab => Lab::from_tuple((x, y, z)).as_rgb(),
+            ClusterSpace::Oklab => Oklab::from_tuple((x, y, z)).as_rgb(),
+        }
+    }
+}
+
+/// Find the `count` dominant colors of the image. Transparent pixels are
+/// ignored.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+///
+/// // Three red pixels and a blue one.
+/// let red = [255, 0, 0, 255];
+/// let blue = [0, 0, 255, 255];
+/// let image = RgbaImage::new(2, 2, [red, red, blue, red].concat()).unwrap();
+///
+/// for method in QuantizeMethod::ALL {
+///     let colors = dominant_colors(&image, 2, method)
+///         .into_iter()
+///         .map(|color| (color.color.as_hex_code(), color.proportion))
+///         .collect::<Vec<_>>();
+///
+///     assert_eq!(
+///         colors,
+///         vec![("ff0000".to_owned(), 0.75), ("0000ff".to_owned(), 0.25)]
+///     );
+/// }
+/// ```
+pub fn dominant_colors(
+    image: &RgbaImage,
+    count: usize,
+    method: QuantizeMethod,
+) -> Vec<DominantColor> {
+    let pixel_count = image.width() as usize * image.height() as usize;
+    let stride = pixel_count.div_ceil(MAX_SAMPLES).max(1);
+    let pixels = image.opaque_pixels().step_by(stride).collect::<Vec<_>>();
+
+    method.quantize(&pixels, count)
+}
+
+/// Split the pixels into `count` boxes in sRGB, by repeatedly splitting the
+/// box with the largest number of pixels times its widest channel range at
+/// its median.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+///
+/// // A cluster of noise around a color, with a mean of exactly that color.
+/// fn cluster((r, g, b): (f64, f64, f64), size: usize) -> Vec<Rgb> {
+///     let noise = |index: usize| (index % 5) as f64 * 4. - 8.;
+///     (0..size)
+///         .map(|i| {
+///             Rgb::from_components((
+///                 r + noise(i),
+///                 g + noise(i / 5),
+///                 b + noise(i / 25),
+///             ))
+///         })
+///         .collect()
+/// }
+///
+/// let pixels = [
+///     cluster((30., 50., 80.), 500),
+///     cluster((220., 70., 50.), 250),
+///     cluster((200., 200., 70.), 250),
+/// ]
+/// .concat();
+///
+/// let colors = median_cut(&pixels, 3)
+///     .into_iter()
+///     .map(|color| (color.color.as_hex_code(), color.proportion))
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(
+///     colors,
+///     vec![
+///         ("1e3250".to_owned(), 0.5),
+///         ("dc4632".to_owned(), 0.25),
+///         ("c8c846".to_owned(), 0.25),
+///     ]
+/// );
+/// ```
+pub fn median_cut(pixels: &[Rgb], count: usize) -> Vec<DominantColor> {
+    if pixels.is_empty() || count == 0 {
+        return vec![];
+    }
+
+    let mut boxes = vec![pixels
+        .iter()
+        .map(|rgb| [rgb.r, rgb.g, rgb.b])
+        .collect::<Vec<_>>()];
+
+    while boxes.len() < count {
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .map(|(index, colors)| {
+                let (channel, range) = widest_channel(colors);
+                (index, channel, range * colors.len() as f64)
+            })
+            .filter(|(_, _, score)| *score > 0.)
+            .max_by(|(_, _, a), (_, _, b)| a.total_cmp(b));
+        let Some((index, channel, _)) = widest else {
+            // Every box only contains a single color.
+            break;
+        };
+
+        let mut lower = boxes.swap_remove(index);
+        lower.sort_by(|a, b| a[channel].total_cmp(&b[channel]));
+        // Split at the median, but keep equal values in the same box.
+        let median = lower[lower.len() / 2][channel];
+        let split = match lower.partition_point(|color| color[channel] < median)
+        {
+            0 => lower.partition_point(|color| color[channel] <= median),
+            split => split,
+        };
+        let upper = lower.split_off(split);
+        boxes.push(lower);
+        boxes.push(upper);
+    }
+
+    let total = pixels.len() as f64;
+    let mut colors = boxes
+        .into_iter()
+        .filter(|colors| !colors.is_empty())
+        .map(|colors| {
+            let [r, g, b] = mean(&colors);
+            DominantColor {
+                color: Rgb::from_components((r, g, b)),
+                proportion: colors.len() as f64 / total,
+            }
+        })
+        .collect::<Vec<_>>();
+    sort_by_proportion(&mut colors);
+
+    colors
+}
+
+/// Cluster the pixels into `count` colors, measuring distances in `space`.
+/// The clusters start out as the median cut colors, so the result is
+/// deterministic.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+///
+/// fn cluster((r, g, b): (f64, f64, f64), size: usize) -> Vec<Rgb> {
+///     let noise = |index: usize| (index % 5) as f64 * 4. - 8.;
+///     (0..size)
+///         .map(|i| {
+///             Rgb::from_components((
+///                 r + noise(i),
+///                 g + noise(i / 5),
+///                 b + noise(i / 25),
+///             ))
+///         })
+///         .collect()
+/// }
+///
+/// // Noisy clusters are recovered in both spaces.
+/// let centers = [(30., 50., 80.), (220., 70., 50.), (200., 200., 70.)];
+/// let pixels = [
+///     cluster(centers[0], 500),
+///     cluster(centers[1], 250),
+///     cluster(centers[2], 250),
+/// ]
+/// .concat();
+///
+/// for space in [ClusterSpace::Lab, ClusterSpace::Oklab] {
+///     let colors = k_means(&pixels, 3, space);
+///
+///     let proportions = colors.iter().map(|color| color.proportion);
+///     assert!(proportions.eq([0.5, 0.25, 0.25]));
+///     for (color, (r, g, b)) in colors.iter().zip(centers) {
+///         // The mean in a perceptual space is close to the mean in sRGB.
+///         let rgb = color.color;
+///         assert!((rgb.r - r).abs() < 1.5, "{:?} {:?}", space, rgb);
+///         assert!((rgb.g - g).abs() < 1.5, "{:?} {:?}", space, rgb);
+///         assert!((rgb.b - b).abs() < 1.5, "{:?} {:?}", space, rgb);
+///     }
+/// }
+///
+/// // On a gradient, k-means moves the median cut colors closer to the
+/// // pixels, measured in OKLab.
+/// let gradient = (0..=255)
+///     .map(|i| Rgb::from_components((i as f64, 255. - i as f64, 64.)))
+///     .collect::<Vec<_>>();
+/// let error = |colors: &[DominantColor]| {
+///     gradient
+///         .iter()
+///         .map(|pixel| {
+///             let (l, a, b) = Oklab::from_rgb(*pixel).as_tuple();
+///             colors
+///                 .iter()
+///                 .map(|color| {
+///                     let (cl, ca, cb) = Oklab::from_rgb(color.color).as_tuple();
+///                     (l - cl).powi(2) + (a - ca).powi(2) + (b - cb).powi(2)
+///                 })
+///                 .fold(f64::INFINITY, f64::min)
+///         })
+///         .sum::<f64>()
+/// };
+///
+/// let seeds = median_cut(&gradient, 4);
+/// let clustered = k_means(&gradient, 4, ClusterSpace::Oklab);
+/// assert!(error(&clustered) < error(&seeds) * 0.97);
+/// ```
+pub fn k_means(
+    pixels: &[Rgb],
+    count: usize,
+    space: ClusterSpace,
+) -> Vec<DominantColor> {
+    let points = pixels
+        .iter()
+        .map(|rgb| space.coordinates(*rgb))
+        .collect::<Vec<_>>();
+    let mut centroids = median_cut(pixels, count)
+        .into_iter()
+        .map(|color| space.coordinates(color.color))
+        .collect::<Vec<_>>();
+    if centroids.is_empty() {
+        return vec![];
+    }
+
+    let mut assignments = vec![usize::MAX; points.len()];
+    for _ in 0..K_MEANS_ITERATIONS {
+        let mut changed = false;
+        for (point, assignment) in points.iter().zip(assignments.iter_mut()) {
+            let nearest = nearest_centroid(&centroids, point);
+            if nearest != *assignment {
+                *assignment = nearest;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![([0.; 3], 0usize); centroids.len()];
+        for (point, assignment) in points.iter().zip(assignments.iter()) {
+            let (sum, members) = &mut sums[*assignment];
+            *sum = [sum[0] + point[0], sum[1] + point[1], sum[2] + point[2]];
+            *members += 1;
+        }
+        for (centroid, (sum, members)) in centroids.iter_mut().zip(sums) {
+            // Empty clusters keep their previous position.
+            if members > 0 {
+                *centroid = sum.map(|value| value / members as f64);
+            }
+        }
+    }
+
+    let total = points.len() as f64;
+    let mut colors = centroids
+        .iter()
+        .enumerate()
+        .map(|(index, centroid)| {
+            let members = assignments
+                .iter()
+                .filter(|assignment| **assignment == index)
+                .count();
+            DominantColor {
+                color: space.rgb(*centroid),
+                proportion: members as f64 / total,
+            }
+        })
+        .filter(|color| color.proportion > 0.)
+        .collect::<Vec<_>>();
+    sort_by_proportion(&mut colors);
+
+    colors
+}
+
+/// The channel with the largest range of values, and that range.
+fn widest_channel(colors: &[[f64; 3]]) -> (usize, f64) {
+    (0..3)
+        .map(|channel| {
+            let (min, max) = colors.iter().fold(
+                (f64::INFINITY, f64::NEG_INFINITY),
+                |(min, max), color| {
+                    (min.min(color[channel]), max.max(color[channel]))
+                },
+            );
+            (channel, max - min)
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .unwrap_or((0, 0.))
+}
+
+fn mean(points: &[[f64; 3]]) -> [f64; 3] {
+    let sum = points.iter().fold([0.; 3], |sum, point| {
+        [sum[0] + point[0], sum[1] + point[1], sum[2] + point[2]]
+    });
+    let count = points.len() as f64;
+    [sum[0] / count, sum[1] / count, sum[2] / count]
+}
+
+fn nearest_centroid(centroids: &[[f64; 3]], point: &[f64; 3]) -> usize {
+    let distance = |centroid: &[f64; 3]| {
+        (0..3)
+            .map(|index| (centroid[index] - point[index]).powi(2))
+            .sum::<f64>()
+    };
+
+    centroids
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| distance(a).total_cmp(&distance(b)))
+        .map_or(0, |(index, _)| index)
+}
+
+fn sort_by_proportion(colors: &mut [DominantColor]) {
+    colors.sort_by(|a, b| b.proportion.total_cmp(&a.proportion));
+}
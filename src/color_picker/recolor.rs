@@ -0,0 +1,327 @@
+//! Reducing images to the colors of a palette, optionally with dithering.
+//!
+//! Sources:
+//! - Redmean: https://www.compuphase.com/cmetric.htm
+//! - Error diffusion kernels: https://tannerhelland.com/2012/12/28/dithering-eleven-algorithms-source-code.html
+//! - Ordered dithering: https://en.wikipedia.org/wiki/Ordered_dithering
+
+use super::*;
+use std::collections::HashMap;
+
+/// How the distance between colors is measured when finding the nearest
+/// palette color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DistanceMetric {
+    /// Euclidean distance between sRGB values.
+    Srgb,
+    /// Euclidean sRGB distance with channels weighted by the mean red value.
+    Redmean,
+    /// CIE76 ΔE, the Euclidean distance in CIE Lab.
+    Cie76,
+    /// Euclidean distance in OKLab.
+    Oklab,
+}
+
+impl DistanceMetric {
+    pub const ALL: [DistanceMetric; 4] = [
+        DistanceMetric::Srgb,
+        DistanceMetric::Redmean,
+        DistanceMetric::Cie76,
+        DistanceMetric::Oklab,
+    ];
+
+    /// Coordinates in which the squared Euclidean distance is the metric,
+    /// except for redmean which is weighted in [Self::distance_squared].
+    fn coordinates(&self, rgb: Rgb) -> [f64; 3] {
+        let (x, y, z) = match self {
+            DistanceMetric::Srgb | DistanceMetric::Redmean => {
+                (rgb.r, rgb.g, rgb.b)
+            }
+            DistanceMetric::Cie76 => Lab::from_rgb(rgb).as_tuple(),
+            DistanceMetric::Oklab => Oklab::from_rgb(rgb).as_tuple(),
+        };
+        [x, y, z]
+    }
+
+    fn distance_squared(&self, a: &[f64; 3], b: &[f64; 3]) -> f64 {
+        let [dx, dy, dz] = [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+        match self {
+            DistanceMetric::Redmean => {
+                let red_mean = (a[0] + b[0]) / 2.;
+                (2. + red_mean / 256.) * dx * dx
+                    + 4. * dy * dy
+                    + (2. + (255. - red_mean) / 256.) * dz * dz
+            }
+            _ => dx * dx + dy * dy + dz * dz,
+        }
+    }
+}
+
+impl UiDisplay for DistanceMetric {
+    type Environment = ();
+
+    fn to_ui_string(&self, _env: Self::Environment) -> String {
+        match self {
+            DistanceMetric::Srgb => "sRGB",
+            DistanceMetric::Redmean => "Redmean",
+            DistanceMetric::Cie76 => "ΔE (CIE76)",
+            DistanceMetric::Oklab => "OKLab",
+        }
+        .to_owned()
+    }
+}
+
+impl Display for DistanceMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DistanceMetric::Srgb => "Srgb",
+            DistanceMetric::Redmean => "Redmean",
+            DistanceMetric::Cie76 => "Cie76",
+            DistanceMetric::Oklab => "Oklab",
+        })
+    }
+}
+
+impl FromStr for DistanceMetric {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Srgb" => Ok(DistanceMetric::Srgb),
+            "Redmean" => Ok(DistanceMetric::Redmean),
+            "Cie76" => Ok(DistanceMetric::Cie76),
+            "Oklab" => Ok(DistanceMetric::Oklab),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dithering {
+    None,
+    FloydSteinberg,
+    Atkinson,
+    /// Ordered dithering with a 4 by 4 Bayer matrix.
+    Bayer,
+}
+
+impl Dithering {
+    pub const ALL: [Dithering; 4] = [
+        Dithering::None,
+        Dithering::FloydSteinberg,
+        Dithering::Atkinson,
+        Dithering::Bayer,
+    ];
+
+    /// Where the quantization error of a pixel is spread, as offsets to the
+    /// pixel and the share of the error.
+    fn diffusion_kernel(&self) -> &'static [(i64, i64, f64)] {
+        match self {
+            Dithering::FloydSteinberg => &[
+                (1, 0, 7. / 16.),
+                (-1, 1, 3. / 16.),
+                (0, 1, 5. / 16.),
+                (1, 1, 1. / 16.),
+            ],
+            // Only 3/4 of the error is spread, which keeps more contrast.
+            Dithering::Atkinson => &[
+                (1, 0, 1. / 8.),
+                (2, 0, 1. / 8.),
+                (-1, 1, 1. / 8.),
+                (0, 1, 1. / 8.),
+                (1, 1, 1. / 8.),
+                (0, 2, 1. / 8.),
+            ],
+            Dithering::None | Dithering::Bayer => &[],
+        }
+    }
+}
+
+impl UiDisplay for Dithering {
+    type Environment = ();
+
+    fn to_ui_string(&self, _env: Self::Environment) -> String {
+        match self {
+            Dithering::None => "None",
+            Dithering::FloydSteinberg => "Floyd–Steinberg",
+            Dithering::Atkinson => "Atkinson",
+            Dithering::Bayer => "Bayer",
+        }
+        .to_owned()
+    }
+}
+
+impl Display for Dithering {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Dithering::None => "None",
+            Dithering::FloydSteinberg => "FloydSteinberg",
+            Dithering::Atkinson => "Atkinson",
+            Dithering::Bayer => "Bayer",
+        })
+    }
+}
+
+impl FromStr for Dithering {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "None" => Ok(Dithering::None),
+            "FloydSteinberg" => Ok(Dithering::FloydSteinberg),
+            "Atkinson" => Ok(Dithering::Atkinson),
+            "Bayer" => Ok(Dithering::Bayer),
+            _ => Err(()),
+        }
+    }
+}
+
+const BAYER_MATRIX: [[u8; 4]; 4] =
+    [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Finds the nearest palette color, remembering the results since images
+/// tend to repeat colors.
+struct NearestColorFinder<'a> {
+    palette: &'a [Rgb],
+    coordinates: Vec<[f64; 3]>,
+    metric: DistanceMetric,
+    cache: HashMap<[u8; 3], usize>,
+}
+
+impl<'a> NearestColorFinder<'a> {
+    fn new(palette: &'a [Rgb], metric: DistanceMetric) -> Self {
+        Self {
+            palette,
+            coordinates: palette
+                .iter()
+                .map(|rgb| metric.coordinates(*rgb))
+                .collect(),
+            metric,
+            cache: HashMap::new(),
+        }
+    }
+
+    fn nearest(&mut self, pixel: [u8; 3]) -> Rgb {
+        let index = *self.cache.entry(pixel).or_insert_with(|| {
+            let [r, g, b] = pixel.map(|channel| channel as f64);
+            let point =
+                self.metric.coordinates(Rgb::from_components((r, g, b)));
+            self.coordinates
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    self.metric
+                        .distance_squared(a, &point)
+                        .total_cmp(&self.metric.distance_squared(b, &point))
+                })
+                .map_or(0, |(index, _)| index)
+        });
+        self.palette[index]
+    }
+}
+
+/// Replace every pixel with the nearest palette color, keeping the alpha.
+/// Fully transparent pixels are left as they are.
+///
+/// Returns `None` if the palette is empty.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+///
+/// let black = Rgb::from_components((0., 0., 0.));
+/// let white = Rgb::from_components((255., 255., 255.));
+///
+/// let gray = [128, 128, 128, 255];
+/// let image = RgbaImage::new(2, 2, [gray; 4].concat()).unwrap();
+///
+/// let white_count = |dithering| {
+///     let recolored = recolor(
+///         &image,
+///         &[black, white],
+///         DistanceMetric::Srgb,
+///         dithering,
+///     )
+///     .unwrap();
+///     recolored.data().chunks(4).filter(|pixel| pixel[0] == 255).count()
+/// };
+///
+/// assert_eq!(white_count(Dithering::None), 4);
+/// assert_eq!(white_count(Dithering::FloydSteinberg), 2);
+/// assert_eq!(white_count(Dithering::Bayer), 2);
+/// ```
+pub fn recolor(
+    image: &RgbaImage,
+    palette: &[Rgb],
+    metric: DistanceMetric,
+    dithering: Dithering,
+) -> Option<RgbaImage> {
+    if palette.is_empty() {
+        return None;
+    }
+
+    let width = image.width() as i64;
+    let height = image.height() as i64;
+    let mut finder = NearestColorFinder::new(palette, metric);
+
+    // The error spread to every pixel by error diffusion.
+    let mut errors = vec![[0.; 3]; (width * height) as usize];
+    let kernel = dithering.diffusion_kernel();
+
+    // Ordered dithering offsets the pixels by about the distance between
+    // palette colors.
+    let bayer_spread = 255. / ((palette.len() - 1).max(1) as f64).cbrt();
+
+    let mut data = image.data().to_vec();
+    for y in 0..height {
+        for x in 0..width {
+            let index = (y * width + x) as usize;
+            let Some([r, g, b, a]) = image.rgba(x, y) else {
+                continue;
+            };
+            if a == 0 {
+                continue;
+            }
+
+            let offset = match dithering {
+                Dithering::Bayer => {
+                    let threshold =
+                        BAYER_MATRIX[(y % 4) as usize][(x % 4) as usize];
+                    ((threshold as f64 + 0.5) / 16. - 0.5) * bayer_spread
+                }
+                _ => 0.,
+            };
+            let error = errors[index];
+            let value = [
+                r as f64 + error[0] + offset,
+                g as f64 + error[1] + offset,
+                b as f64 + error[2] + offset,
+            ];
+
+            let nearest = finder.nearest(
+                value.map(|channel| channel.round().clamp(0., 255.) as u8),
+            );
+            let (r, g, b) = nearest.as_bytes();
+            data[index * 4] = r;
+            data[index * 4 + 1] = g;
+            data[index * 4 + 2] = b;
+
+            let quantization_error = [
+                value[0] - offset - nearest.r,
+                value[1] - offset - nearest.g,
+                value[2] - offset - nearest.b,
+            ];
+            for (dx, dy, share) in kernel {
+                let (target_x, target_y) = (x + dx, y + dy);
+                if target_x < 0 || target_x >= width || target_y >= height {
+                    continue;
+                }
+                let target =
+                    &mut errors[(target_y * width + target_x) as usize];
+                for (channel, error) in quantization_error.iter().enumerate() {
+                    target[channel] += error * share;
+                }
+            }
+        }
+    }
+
+    RgbaImage::new(image.width(), image.height(), data)
+}
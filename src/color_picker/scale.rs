@@ -0,0 +1,317 @@
+//! Generation of tint and shade ramps, like the 50 to 950 color scales of
+//! Tailwind and Material, for use as design tokens.
+
+use super::*;
+
+/// The names of the steps in a scale, from lightest to darkest.
+pub const SCALE_STEPS: [u32; 11] =
+    [50, 100, 200, 300, 400, 500, 600, 700, 800, 900, 950];
+
+/// The index of the step which the base color is placed at.
+const BASE_STEP_INDEX: usize = 5;
+
+/// The cylindrical space which a scale is interpolated in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScaleSpace {
+    Oklch,
+    Lch,
+}
+
+impl ScaleSpace {
+    /// Convert to lightness in the range 0 to 1, chroma, and hue in degrees.
+    fn lch_from_rgb(&self, rgb: Rgb) -> (f64, f64, f64) {
+        let (l, a, b) = match self {
+            ScaleSpace::Oklch => Oklab::from_rgb(rgb).as_tuple(),
+            ScaleSpace::Lch => {
+                let (l, a, b) = Lab::from_rgb(rgb).as_tuple();
+                (l / 100., a, b)
+            }
+        };
+        let (chroma, hue) = rectangular_to_polar(a, b);
+
+        (l, chroma, hue)
+    }
+
+    fn lch_to_linear_srgb(&self, lch: (f64, f64, f64)) -> (f64, f64, f64) {
+        let (a, b) = polar_to_rectangular(lch.1, lch.2);
+        match self {
+            ScaleSpace::Oklch => Oklab { l: lch.0, a, b }.as_linear_srgb(),
+            ScaleSpace::Lch => Lab {
+                l: lch.0 * 100.,
+                a,
+                b,
+            }
+            .as_linear_srgb(),
+        }
+    }
+}
+
+impl UiDisplay for ScaleSpace {
+    type Environment = ();
+
+    fn to_ui_string(&self, _env: Self::Environment) -> String {
+        match self {
+            ScaleSpace::Oklch => "OKLCh",
+            ScaleSpace::Lch => "LCh",
+        }
+        .to_owned()
+    }
+}
+
+impl Display for ScaleSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ScaleSpace::Oklch => "Oklch",
+            ScaleSpace::Lch => "Lch",
+        })
+    }
+}
+
+impl FromStr for ScaleSpace {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Oklch" => Ok(ScaleSpace::Oklch),
+            "Lch" => Ok(ScaleSpace::Lch),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScaleOptions {
+    pub space: ScaleSpace,
+    /// The lightness of the 50 step, in the range 0 to 1.
+    pub lightest: f64,
+    /// The lightness of the 950 step, in the range 0 to 1.
+    pub darkest: f64,
+    /// The exponent of the lightness curve going out from the base color.
+    /// Values above 1 keep the steps closer to the base color, while values
+    /// below 1 push them towards the ends.
+    pub curve: f64,
+    /// How much the chroma is reduced towards the ends of the scale, in the
+    /// range 0 to 1.
+    pub chroma_easing: f64,
+}
+
+impl Default for ScaleOptions {
+    fn default() -> Self {
+        Self {
+            space: ScaleSpace::Oklch,
+            lightest: 0.97,
+            darkest: 0.27,
+            curve: 1.,
+            chroma_easing: 0.5,
+        }
+    }
+}
+
+/// Generate a scale with one color for every step in [SCALE_STEPS], with the
+/// base color placed at 500. Colors which fall outside of sRGB have their
+/// chroma reduced until they fit.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+///
+/// let base = Rgb::from_hex_code("3b82f6").unwrap();
+/// let scale = generate_scale(base, &ScaleOptions::default());
+///
+/// assert_eq!(scale.len(), 11);
+/// assert_eq!(scale[5].0, 500);
+/// assert_eq!(scale[5].1.as_hex_code(), "3b82f6".to_owned());
+/// ```
+pub fn generate_scale(base: Rgb, options: &ScaleOptions) -> Vec<(u32, Rgb)> {
+    let space = options.space;
+    let (base_lightness, base_chroma, hue) = space.lch_from_rgb(base);
+
+    let lightest = options.lightest.max(base_lightness);
+    let darkest = options.darkest.min(base_lightness);
+
+    SCALE_STEPS
+        .iter()
+        .enumerate()
+        .map(|(index, step)| {
+            if index == BASE_STEP_INDEX {
+                return (*step, base);
+            }
+
+            let (extreme, distance) = match index < BASE_STEP_INDEX {
+                true => (
+                    lightest,
+                    (BASE_STEP_INDEX - index) as f64 / BASE_STEP_INDEX as f64,
+                ),
+                false => (
+                    darkest,
+                    (index - BASE_STEP_INDEX) as f64
+                        / (SCALE_STEPS.len() - 1 - BASE_STEP_INDEX) as f64,
+                ),
+            };
+
+            let lightness = base_lightness
+                + (extreme - base_lightness)
+                    * distance.powf(options.curve.max(f64::EPSILON));
+            let chroma = base_chroma
+                * (1. - options.chroma_easing.clamp(0., 1.) * distance.powi(2));
+
+            (*step, fit_chroma_in_gamut(space, (lightness, chroma, hue)))
+        })
+        .collect()
+}
+
+/// Convert to sRGB, reducing the chroma using a binary search if the color is
+/// outside of the gamut.
+fn fit_chroma_in_gamut(space: ScaleSpace, lch: (f64, f64, f64)) -> Rgb {
+    let (lightness, chroma, hue) = lch;
+
+    let mut linear = space.lch_to_linear_srgb(lch);
+    if !linear_srgb_in_gamut(linear) {
+        let (mut low, mut high) = (0., chroma);
+        for _ in 0..24 {
+            let middle = (low + high) / 2.;
+            match linear_srgb_in_gamut(
+                space.lch_to_linear_srgb((lightness, middle, hue)),
+            ) {
+                true => low = middle,
+                false => high = middle,
+            }
+        }
+        linear = space.lch_to_linear_srgb((lightness, low, hue));
+    }
+
+    Rgb::from_floats((
+        linear_to_srgb(linear.0.clamp(0., 1.)),
+        linear_to_srgb(linear.1.clamp(0., 1.)),
+        linear_to_srgb(linear.2.clamp(0., 1.)),
+    ))
+}
+
+/// A format which scales can be exported as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScaleFormat {
+    Css,
+    Tailwind,
+    Scss,
+    Json,
+}
+
+impl ScaleFormat {
+    /// Format a scale as design tokens named `name`. Characters which aren't
+    /// allowed in token names are removed from the name.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::color_picker::*;
+    ///
+    /// let scale = vec![
+    ///     (50, Rgb::from_components((255., 255., 255.))),
+    ///     (100, Rgb::from_components((0., 0., 0.))),
+    /// ];
+    ///
+    /// assert_eq!(
+    ///     ScaleFormat::Css.format("Brand blue", &scale),
+    ///     ":root {\n  --brand-blue-50: #ffffff;\n  --brand-blue-100: #000000;\n}\n"
+    ///         .to_owned()
+    /// );
+    /// assert_eq!(
+    ///     ScaleFormat::Scss.format("primary", &scale),
+    ///     "$primary-50: #ffffff;\n$primary-100: #000000;\n".to_owned()
+    /// );
+    /// ```
+    pub fn format(&self, name: &str, scale: &[(u32, Rgb)]) -> String {
+        let name = token_name(name);
+        let entries = scale
+            .iter()
+            .map(|(step, rgb)| (step, format!("#{}", rgb.as_hex_code())));
+
+        match self {
+            ScaleFormat::Css => {
+                let properties = entries
+                    .map(|(step, hex)| format!("  --{name}-{step}: {hex};\n"))
+                    .collect::<String>();
+                format!(":root {{\n{properties}}}\n")
+            }
+            ScaleFormat::Tailwind => {
+                let colors = entries
+                    .map(|(step, hex)| format!("          {step}: '{hex}',\n"))
+                    .collect::<String>();
+                format!(
+                    "module.exports = {{\n  theme: {{\n    extend: {{\n      \
+                     colors: {{\n        '{name}': {{\n{colors}        }},\n      \
+                     }},\n    }},\n  }},\n}};\n"
+                )
+            }
+            ScaleFormat::Scss => entries
+                .map(|(step, hex)| format!("${name}-{step}: {hex};\n"))
+                .collect(),
+            ScaleFormat::Json => {
+                let colors = entries
+                    .map(|(step, hex)| format!("    \"{step}\": \"{hex}\""))
+                    .collect::<Vec<_>>()
+                    .join(",\n");
+                format!("{{\n  \"{name}\": {{\n{colors}\n  }}\n}}\n")
+            }
+        }
+    }
+}
+
+impl UiDisplay for ScaleFormat {
+    type Environment = ();
+
+    fn to_ui_string(&self, _env: Self::Environment) -> String {
+        match self {
+            ScaleFormat::Css => "CSS",
+            ScaleFormat::Tailwind => "Tailwind",
+            ScaleFormat::Scss => "SCSS",
+            ScaleFormat::Json => "JSON",
+        }
+        .to_owned()
+    }
+}
+
+impl Display for ScaleFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ScaleFormat::Css => "Css",
+            ScaleFormat::Tailwind => "Tailwind",
+            ScaleFormat::Scss => "Scss",
+            ScaleFormat::Json => "Json",
+        })
+    }
+}
+
+impl FromStr for ScaleFormat {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Css" => Ok(ScaleFormat::Css),
+            "Tailwind" => Ok(ScaleFormat::Tailwind),
+            "Scss" => Ok(ScaleFormat::Scss),
+            "Json" => Ok(ScaleFormat::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Turn a name into a lowercase, kebab-case token name, falling back to
+/// `color` if nothing is left.
+fn token_name(name: &str) -> String {
+    let name = name
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|char| {
+                    char.is_ascii_alphanumeric() || *char == '-' || *char == '_'
+                })
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    match name.is_empty() {
+        true => "color".to_owned(),
+        false => name,
+    }
+}
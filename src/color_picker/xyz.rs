@@ -0,0 +1,238 @@
+use super::*;
+
+/// A row major 3x3 matrix, used for linear color space transformations.
+pub type Matrix3 = [[f64; 3]; 3];
+
+/// The CIE 1931 XYZ coordinates of the D65 white point, with `Y = 1`.
+pub const WHITE_D65: (f64, f64, f64) = (0.95047, 1., 1.08883);
+/// The CIE 1931 XYZ coordinates of the D50 white point, which is used by ICC
+/// profile connection spaces and CSS `lab()`, with `Y = 1`.
+pub const WHITE_D50: (f64, f64, f64) = (0.9642, 1., 0.8249);
+/// The CIE 1931 XYZ coordinates of the white point used by ACES (which is
+/// approximately D60), with `Y = 1`.
+pub const WHITE_D60: (f64, f64, f64) = (0.952646, 1., 1.008825);
+
+/// Source: http://www.brucelindbloom.com/index.html?Eqn_RGB_XYZ_Matrix.html
+pub const LINEAR_SRGB_TO_XYZ: Matrix3 = [
+    [0.4124564, 0.3575761, 0.1804375],
+    [0.2126729, 0.7151522, 0.0721750],
+    [0.0193339, 0.1191920, 0.9503041],
+];
+/// Source: http://www.brucelindbloom.com/index.html?Eqn_RGB_XYZ_Matrix.html
+pub const XYZ_TO_LINEAR_SRGB: Matrix3 = [
+    [3.2404542, -1.5371385, -0.4985314],
+    [-0.9692660, 1.8760108, 0.0415560],
+    [0.0556434, -0.2040259, 1.0572252],
+];
+
+/// Source: http://www.brucelindbloom.com/index.html?Eqn_ChromAdapt.html
+const BRADFORD: Matrix3 = [
+    [0.8951000, 0.2664000, -0.1614000],
+    [-0.7502000, 1.7135000, 0.0367000],
+    [0.0389000, -0.0685000, 1.0296000],
+];
+const BRADFORD_INVERSE: Matrix3 = [
+    [0.9869929, -0.1470543, 0.1599627],
+    [0.4323053, 0.5183603, 0.0492912],
+    [-0.0085287, 0.0400428, 0.9684867],
+];
+
+pub fn multiply_matrix_vector(
+    matrix: &Matrix3,
+    vector: (f64, f64, f64),
+) -> (f64, f64, f64) {
+    let row = |row: [f64; 3]| {
+        row[0] * vector.0 + row[1] * vector.1 + row[2] * vector.2
+    };
+
+    (row(matrix[0]), row(matrix[1]), row(matrix[2]))
+}
+
+pub fn multiply_matrices(a: &Matrix3, b: &Matrix3) -> Matrix3 {
+    let mut result = [[0.; 3]; 3];
+    for (i, row) in result.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
+            *cell = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    result
+}
+
+/// Create a matrix which adapts XYZ colors from one white point to another
+/// using the Bradford transform.
+///
+/// # Examples
+/// ```
+/// use lax_utils::color_picker::*;
+/// use approx::assert_relative_eq;
+///
+/// let matrix = chromatic_adaptation_matrix(WHITE_D65, WHITE_D60);
+/// let white = multiply_matrix_vector(&matrix, WHITE_D65);
+///
+/// assert_relative_eq!(white.0, WHITE_D60.0, epsilon = 1e-6);
+/// assert_relative_eq!(white.1, WHITE_D60.1, epsilon = 1e-6);
+/// assert_relative_eq!(white.2, WHITE_D60.2, epsilon = 1e-6);
+/// ```
+pub fn chromatic_adaptation_matrix(
+    source_white: (f64, f64, f64),
+    destination_white: (f64, f64, f64),
+) -> Matrix3 {
+    let source = multiply_matrix_vector(&BRADFORD, source_white);
+    let destination = multiply_matrix_vector(&BRADFORD, destination_white);
+
+    let scale = [
+        [destination.0 / source.0, 0., 0.],
+        [0., destination.1 / source.1, 0.],
+        [0., 0., destination.2 / source.2],
+    ];
+
+    multiply_matrices(&BRADFORD_INVERSE, &multiply_matrices(&scale, &BRADFORD))
+}
+
+/// Convert a gamma encoded sRGB channel in the range 0 to 1 to linear light.
+///
+/// Source: https://en.wikipedia.org/wiki/SRGB#From_sRGB_to_CIE_XYZ
+pub fn srgb_to_linear(value: f64) -> f64 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convert a linear light channel in the range 0 to 1 to gamma encoded sRGB.
+///
+/// Source: https://en.wikipedia.org/wiki/SRGB#From_CIE_XYZ_to_sRGB
+pub fn linear_to_srgb(value: f64) -> f64 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1. / 2.4) - 0.055
+    }
+}
+
+/// A CIE 1931 XYZ color relative to the D65 white point, where `Y = 1` is the
+/// luminance of sRGB white.
+///
+/// This isn't a [Color] by itself, but is used as the connection space for
+/// converting between sRGB and other colorimetric spaces.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Xyz {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Xyz {
+    pub fn as_tuple(&self) -> (f64, f64, f64) {
+        (self.x, self.y, self.z)
+    }
+
+    pub fn from_tuple(tuple: (f64, f64, f64)) -> Self {
+        Self {
+            x: tuple.0,
+            y: tuple.1,
+            z: tuple.2,
+        }
+    }
+
+    pub fn from_rgb(rgb: Rgb) -> Self {
+        let (r, g, b) = rgb.as_floats();
+        let linear = (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b));
+
+        Self::from_tuple(multiply_matrix_vector(&LINEAR_SRGB_TO_XYZ, linear))
+    }
+
+    /// Convert to sRGB, clipping any channels that fall outside of the gamut.
+    pub fn as_rgb(&self) -> Rgb {
+        let (r, g, b) =
+            multiply_matrix_vector(&XYZ_TO_LINEAR_SRGB, self.as_tuple());
+
+        Rgb::from_floats((
+            linear_to_srgb(r.clamp(0., 1.)),
+            linear_to_srgb(g.clamp(0., 1.)),
+            linear_to_srgb(b.clamp(0., 1.)),
+        ))
+    }
+}
+
+/// Invert a matrix, returning `None` if it's singular.
+pub fn invert_matrix(matrix: &Matrix3) -> Option<Matrix3> {
+    let [[a, b, c], [d, e, f], [g, h, i]] = *matrix;
+
+    let determinant =
+        a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g);
+    if determinant.abs() < f64::EPSILON {
+        return None;
+    }
+
+    let inverse = [
+        [e * i - f * h, c * h - b * i, b * f - c * e],
+        [f * g - d * i, a * i - c * g, c * d - a * f],
+        [d * h - e * g, b * g - a * h, a * e - b * d],
+    ];
+
+    Some(inverse.map(|row| row.map(|cell| cell / determinant)))
+}
+
+/// Convert an XYZ color to CIE L\*a\*b\* relative to the specified white
+/// point. L is in the range 0 to 100, while a and b are roughly in the range
+/// -128 to 127.
+///
+/// Source: http://www.brucelindbloom.com/index.html?Eqn_XYZ_to_Lab.html
+pub fn xyz_to_lab(
+    xyz: (f64, f64, f64),
+    white: (f64, f64, f64),
+) -> (f64, f64, f64) {
+    const EPSILON: f64 = 216. / 24389.;
+    const KAPPA: f64 = 24389. / 27.;
+
+    let f = |t: f64| {
+        if t > EPSILON {
+            t.cbrt()
+        } else {
+            (KAPPA * t + 16.) / 116.
+        }
+    };
+
+    let fx = f(xyz.0 / white.0);
+    let fy = f(xyz.1 / white.1);
+    let fz = f(xyz.2 / white.2);
+
+    (116. * fy - 16., 500. * (fx - fy), 200. * (fy - fz))
+}
+
+/// The inverse of [xyz_to_lab].
+///
+/// Source: http://www.brucelindbloom.com/index.html?Eqn_Lab_to_XYZ.html
+pub fn lab_to_xyz(
+    lab: (f64, f64, f64),
+    white: (f64, f64, f64),
+) -> (f64, f64, f64) {
+    const EPSILON: f64 = 216. / 24389.;
+    const KAPPA: f64 = 24389. / 27.;
+
+    let fy = (lab.0 + 16.) / 116.;
+    let fx = fy + lab.1 / 500.;
+    let fz = fy - lab.2 / 200.;
+
+    let f_inverse = |t: f64| {
+        if t.powi(3) > EPSILON {
+            t.powi(3)
+        } else {
+            (116. * t - 16.) / KAPPA
+        }
+    };
+
+    let y = if lab.0 > KAPPA * EPSILON {
+        fy.powi(3)
+    } else {
+        lab.0 / KAPPA
+    };
+
+    (
+        f_inverse(fx) * white.0,
+        y * white.1,
+        f_inverse(fz) * white.2,
+    )
+}
@@ -1,4 +1,4 @@
-use crate::{toasts, utils::*};
+use crate::{string_utils::*, toasts, utils::*};
 use gloo_events::EventListener;
 use leptos::{html::*, leptos_dom::helpers::*, logging::error, window, *};
 use leptos_router::*;
@@ -148,26 +148,246 @@ pub fn Icon(
     }
 }
 
+/// A text input for a number, which can also be changed by dragging its
+/// prefix sideways, scrolling over it while it's focused, or with the up and
+/// down arrow keys. Holding Shift makes the steps ten times larger, and Alt
+/// ten times smaller.
+///
+/// Numbers can be typed as percentages of `max`, or as angles if `full_turn`
+/// is given.
 #[component]
-pub fn LabeledFloatInput(
+pub fn NumberInput<F>(
+    #[prop(into)] value: Signal<f64>,
+    /// Called with every valid number that's entered, clamped to `min` and
+    /// `max`.
+    set_value: F,
+    #[prop(into)] min: MaybeSignal<f64>,
+    #[prop(into)] max: MaybeSignal<f64>,
+    #[prop(into)] step: MaybeSignal<f64>,
+    /// The number an angle of a full turn stands for, if the number is an
+    /// angle.
+    #[prop(into, optional)]
+    full_turn: MaybeSignal<Option<f64>>,
+    /// How many decimals are shown at most.
+    #[prop(into)]
+    decimals: MaybeSignal<usize>,
+    /// How many decimals are always shown.
+    #[prop(optional)]
+    min_decimals: usize,
     #[prop(into)] prefix: MaybeSignal<Option<String>>,
     #[prop(into)] postfix: MaybeSignal<Option<String>>,
-    children: Children,
+    #[prop(optional)] placeholder: &'static str,
 ) -> impl IntoView
 where
+    F: Fn(f64) + Copy + 'static,
 {
+    // Values are compared with at least this many decimals when syncing the
+    // input, so that floating point errors don't overwrite what was typed.
+    const SYNC_DECIMALS: usize = 2;
+
+    let input_ref = create_node_ref::<Input>();
+    let invalid = create_rw_signal(false);
+    let error_id = format!("number-input-error-{}", unique_id());
+
+    let format_value = move |value: f64| {
+        naturally_format_float(value, min_decimals, decimals.get_untracked())
+    };
+    // Explains what can be typed, shown for invalid input.
+    let expected_format = move || {
+        let angle = match full_turn() {
+            Some(_) => ", an angle like 90deg",
+            None => "",
+        };
+        format!(
+            "Expected a number from {} to {}, a percentage like 50%{}, or an \
+             expression like 1+2",
+            format_value(min()),
+            format_value(max()),
+            angle,
+        )
+    };
+
+    let parse = move |text: &str| {
+        let units = NumberUnits {
+            full_percent: max.get_untracked(),
+            full_turn: full_turn.get_untracked(),
+        };
+        text.parse_number(units)
+    };
+
+    // The input is forced to update when the number of decimals changes. What
+    // was typed is parsed with units, so `50%` isn't replaced while typing.
+    create_effect(move |synced_decimals: Option<Option<usize>>| {
+        let value = value();
+        let decimals = decimals();
+        let input = input_ref.get()?;
+
+        let digits = -(decimals.max(SYNC_DECIMALS) as i32);
+        let typed = parse(&input.value());
+        if typed.is_none_or(|typed| !value.float_compare_digits(typed, digits))
+            || synced_decimals.flatten() != Some(decimals)
+        {
+            input.set_value(&format_value(value));
+        }
+        Some(decimals)
+    });
+
+    let clamp =
+        move |value: f64| value.clamp(min.get_untracked(), max.get_untracked());
+    // Set the value and show it in the input.
+    let commit = move |value: f64| {
+        let value = clamp(value);
+        set_value(value);
+        if let Some(input) = input_ref.get_untracked() {
+            input.set_value(&format_value(value));
+        }
+    };
+    let step_by = move |steps: f64, shift: bool, alt: bool| {
+        let factor = match (shift, alt) {
+            (true, false) => 10.,
+            (false, true) => 0.1,
+            _ => 1.,
+        };
+        commit(value.get_untracked() + steps * factor * step.get_untracked());
+    };
+
+    let on_input = move |ev: ev::Event| {
+        // Expressions are only evaluated on change, since partially typed ones
+        // like `200*0` are valid too.
+        let text = event_target_value(&ev);
+        let is_expression = text
+            .trim()
+            .trim_start_matches(['-', '+'])
+            .contains(['+', '-', '*', '/', '(', ')']);
+        if is_expression {
+            invalid.set(false);
+            return;
+        }
+
+        let parsed = parse(&text);
+        invalid.set(parsed.is_none() && !text.trim().is_empty());
+        if let Some(value) = parsed {
+            set_value(clamp(value));
+        }
+    };
+    let on_change = move |ev: ev::Event| {
+        let text = event_target_value(&ev);
+        invalid.set(false);
+
+        // Invalid text is replaced by the last valid value.
+        let Some(parsed) = parse(&text) else {
+            if !text.trim().is_empty() {
+                toasts::add_toast(format!(
+                    "Couldn't read '{}'. {}",
+                    text.trim(),
+                    expected_format()
+                ));
+            }
+            return commit(value.get_untracked());
+        };
+        commit(parsed);
+    };
+
+    let on_key_down = move |ev: ev::KeyboardEvent| {
+        let steps = match ev.key().as_str() {
+            "ArrowUp" => 1.,
+            "ArrowDown" => -1.,
+            _ => return,
+        };
+        ev.prevent_default();
+        step_by(steps, ev.shift_key(), ev.alt_key());
+    };
+
+    // Scrolling only changes the value of focused inputs, to not get in the
+    // way of scrolling the page. The listener is added to the input itself,
+    // since delegated wheel listeners are passive and can't stop the page from
+    // scrolling.
+    input_ref.on_load(move |input| {
+        let mut wheel = ev::Custom::<ev::WheelEvent>::new("wheel");
+        wheel.options_mut().passive(false);
+        let _ = input.on(wheel, move |ev| {
+            let focused = input_ref.get_untracked().is_some_and(|input| {
+                document().active_element().as_deref() == Some(&**input)
+            });
+            if !focused || ev.delta_y() == 0. {
+                return;
+            }
+            ev.prevent_default();
+            step_by(-ev.delta_y().signum(), ev.shift_key(), ev.alt_key());
+        });
+    });
+
+    // Dragging the prefix changes the value by a step per pixel.
+    let scrub_start = store_value::<Option<(i32, f64)>>(None);
+    let on_scrub_start = move |ev: ev::PointerEvent| {
+        if ev.button() != 0 || scrub_start.get_value().is_some() {
+            return;
+        }
+        let target = event_target::<web_sys::Element>(&ev);
+        if target.set_pointer_capture(ev.pointer_id()).is_err() {
+            return;
+        }
+        ev.prevent_default();
+        scrub_start.set_value(Some((ev.client_x(), value.get_untracked())));
+    };
+    let on_scrub_move = move |ev: ev::PointerEvent| {
+        let Some((start_x, start_value)) = scrub_start.get_value() else {
+            return;
+        };
+        let factor = match (ev.shift_key(), ev.alt_key()) {
+            (true, false) => 10.,
+            (false, true) => 0.1,
+            _ => 1.,
+        };
+        let pixels = (ev.client_x() - start_x) as f64;
+        commit(start_value + pixels * factor * step.get_untracked());
+    };
+    let on_scrub_end = move |_: ev::PointerEvent| {
+        scrub_start.set_value(None);
+    };
+
     let prefix = move || prefix().unwrap_or("".to_string());
     let postfix = move || postfix().unwrap_or("".to_string());
     view! {
-        <div class="labeled-input">
-            <span class="prefix">
+        <div class="labeled-input number-input">
+            <span
+                class="prefix"
+                title="Drag to change"
+                on:pointerdown=on_scrub_start
+                on:pointermove=on_scrub_move
+                on:pointerup=on_scrub_end
+                on:pointercancel=on_scrub_end
+                on:lostpointercapture=on_scrub_end
+            >
                 {prefix}
             </span>
             <span class="postfix">
                 {postfix}
             </span>
             <span class="input">
-                {children()}
+                <input
+                    type="text"
+                    inputmode="decimal"
+                    size=6
+                    placeholder=placeholder
+                    role="spinbutton"
+                    aria-valuemin=move || min().to_string()
+                    aria-valuemax=move || max().to_string()
+                    aria-valuenow=move || value().to_string()
+                    aria-invalid=move || invalid().to_string()
+                    aria-errormessage=error_id.clone()
+                    on:input=on_input
+                    on:change=on_change
+                    on:keydown=on_key_down
+                    _ref=input_ref
+                />
+            </span>
+            <span
+                class="error-message"
+                id=error_id
+                hidden=move || !invalid()
+            >
+                {expected_format}
             </span>
         </div>
     }
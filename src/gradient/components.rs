@@ -0,0 +1,289 @@
+use crate::{
+    color_picker::{components::*, *},
+    components::*,
+    gradient::*,
+    settings::StoredRadioGroup,
+    utils::*,
+};
+use leptos::{ev::*, *};
+
+/// How many colors are sampled between every pair of stops in the preview and
+/// the baked export.
+const DEFAULT_BAKED_SAMPLES: usize = 8;
+
+#[component]
+pub fn GradientBuilder() -> impl IntoView {
+    let space_options = InterpolationSpace::ALL
+        .into_iter()
+        .map(|space| (space.to_ui_string(()), space))
+        .collect::<Vec<_>>();
+    let hue_options = vec![
+        ("Shorter".to_owned(), HueInterpolation::Shorter),
+        ("Longer".to_owned(), HueInterpolation::Longer),
+        ("Increasing".to_owned(), HueInterpolation::Increasing),
+        ("Decreasing".to_owned(), HueInterpolation::Decreasing),
+    ];
+
+    let (space, set_space) = create_signal(InterpolationSpace::Oklch);
+    let (hue_interpolation, set_hue_interpolation) =
+        create_signal(HueInterpolation::Shorter);
+    let (angle, set_angle) = create_signal(90.);
+    let (baked_samples, set_baked_samples) =
+        create_signal(DEFAULT_BAKED_SAMPLES);
+
+    // Every stop gets an id, so that its inputs aren't recreated while they're
+    // being dragged.
+    let first_stop_id = unique_id();
+    let stops = create_rw_signal(vec![
+        (
+            first_stop_id,
+            GradientStop {
+                color: Rgb::from_components((255., 0., 0.)),
+                position: 0.,
+            },
+        ),
+        (
+            unique_id(),
+            GradientStop {
+                color: Rgb::from_components((0., 0., 255.)),
+                position: 1.,
+            },
+        ),
+    ]);
+    let selected = create_rw_signal(first_stop_id);
+
+    let stop_by_id = move |id: u64| {
+        stops.with(|stops| {
+            stops
+                .iter()
+                .find(|(stop_id, _)| *stop_id == id)
+                .map(|(_, stop)| *stop)
+        })
+    };
+    let update_stop = move |id: u64, update: &dyn Fn(&mut GradientStop)| {
+        stops.update(|stops| {
+            if let Some((_, stop)) =
+                stops.iter_mut().find(|(stop_id, _)| *stop_id == id)
+            {
+                update(stop);
+            }
+        });
+    };
+
+    let picker_color = create_rw_signal(DynamicColor::from_color(
+        stop_by_id(first_stop_id)
+            .map_or(Rgb::from_components((255., 255., 255.)), |stop| {
+                stop.color
+            }),
+    ));
+
+    // Edits in the picker are written to the selected stop.
+    create_effect(move |_| {
+        let rgb = picker_color().to_color::<Rgb>();
+        let id = selected.get_untracked();
+        if stop_by_id(id).is_some_and(|stop| stop.color != rgb) {
+            update_stop(id, &|stop| stop.color = rgb);
+        }
+    });
+
+    let select_stop = move |id: u64| {
+        selected.set(id);
+        if let Some(stop) = stop_by_id(id) {
+            picker_color.set(
+                DynamicColor::from_color(stop.color).set_color_space(
+                    picker_color.get_untracked().color_space(),
+                ),
+            );
+        }
+    };
+
+    let gradient = create_memo(move |_| Gradient {
+        stops: stops().into_iter().map(|(_, stop)| stop).collect(),
+        angle: angle(),
+        space: space(),
+        hue_interpolation: hue_interpolation(),
+    });
+
+    let css =
+        Signal::derive(move || gradient.with(|gradient| gradient.to_css()));
+    let baked_css = Signal::derive(move || {
+        gradient.with(|gradient| gradient.to_baked_css(baked_samples()))
+    });
+
+    let preview_style = move || format!("background: {};", baked_css());
+
+    let add_stop = move |_| {
+        let gradient = gradient.get_untracked();
+        let sorted = gradient.sorted_stops();
+
+        // Put the new stop in the middle of the largest gap.
+        let position = sorted
+            .iter()
+            .zip(sorted.iter().skip(1))
+            .max_by(|(a_start, a_end), (b_start, b_end)| {
+                (a_end.position - a_start.position)
+                    .total_cmp(&(b_end.position - b_start.position))
+            })
+            .map_or(0.5, |(start, end)| (start.position + end.position) / 2.);
+        let color = gradient
+            .sample(position)
+            .unwrap_or(Rgb::from_components((255., 255., 255.)));
+
+        let id = unique_id();
+        stops
+            .update(|stops| stops.push((id, GradientStop { color, position })));
+        select_stop(id);
+    };
+
+    let remove_stop = move |id: u64| {
+        stops.update(|stops| stops.retain(|(stop_id, _)| *stop_id != id));
+        if selected.get_untracked() == id {
+            if let Some((first_id, _)) =
+                stops.with_untracked(|stops| stops.first().copied())
+            {
+                select_stop(first_id);
+            }
+        }
+    };
+
+    let stop_view = move |id: u64| {
+        let color_style =
+            move || stop_by_id(id).map(|stop| stop.color.as_css_variables());
+        let position =
+            move || stop_by_id(id).map_or(0., |stop| stop.position * 100.);
+
+        let on_position_input = move |ev: Event| {
+            let Ok(value) = event_target_value(&ev).parse::<f64>() else {
+                return;
+            };
+            update_stop(id, &|stop| stop.position = value / 100.);
+        };
+
+        view! {
+            <li
+                class="stop"
+                class:selected=move || selected() == id
+            >
+                <button
+                    class="color"
+                    style=color_style
+                    aria-label="Edit stop color"
+                    title="Edit stop color"
+                    on:click=move |_| select_stop(id)
+                />
+                <input
+                    type="range"
+                    min="0"
+                    max="100"
+                    step="0.5"
+                    aria-label="Stop position"
+                    prop:value=position
+                    on:input=on_position_input
+                />
+                <span class="position">
+                    {move || format!("{}%", naturally_format_float(position(), 0, 1))}
+                </span>
+                <button
+                    class="remove"
+                    aria-label="Remove stop"
+                    title="Remove stop"
+                    disabled=move || stops.with(|stops| stops.len() <= 2)
+                    on:click=move |_| remove_stop(id)
+                >
+                    <Icon icon_id="x"/>
+                </button>
+            </li>
+        }
+    };
+
+    let on_angle_input = move |ev: Event| {
+        if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+            set_angle(value);
+        }
+    };
+    let on_baked_samples_input = move |ev: Event| {
+        if let Ok(value) = event_target_value(&ev).parse::<usize>() {
+            set_baked_samples(value.clamp(1, 64));
+        }
+    };
+
+    view! {
+        <div class="gradient-builder">
+            <div class="preview" style=preview_style/>
+            <div class="editor">
+                <section class="tool-panel stops">
+                    <h2>"Stops"</h2>
+                    <ul>
+                        <For
+                            each=move || {
+                                stops.with(|stops| {
+                                    stops.iter().map(|(id, _)| *id).collect::<Vec<_>>()
+                                })
+                            }
+                            key=|id| *id
+                            children=stop_view
+                        />
+                    </ul>
+                    <button class="add" on:click=add_stop>
+                        <Icon icon_id="plus"/>
+                        "Add Stop"
+                    </button>
+                </section>
+                <ColorPicker color=picker_color/>
+            </div>
+            <section class="tool-panel options">
+                <h2>"Interpolation"</h2>
+                <StoredRadioGroup
+                    options=space_options
+                    title="Color Space".to_owned()
+                    name=Signal::derive(|| "gradient-space".to_owned())
+                    on_change=set_space
+                    key="s_gradient_space"
+                />
+                <StoredRadioGroup
+                    options=hue_options
+                    title="Hue Path".to_owned()
+                    name=Signal::derive(|| "gradient-hue".to_owned())
+                    on_change=set_hue_interpolation
+                    key="s_gradient_hue"
+                />
+                <label>
+                    "Angle"
+                    <input
+                        type="number"
+                        step="1"
+                        prop:value=angle
+                        on:input=on_angle_input
+                    />
+                    "°"
+                </label>
+            </section>
+            <section class="tool-panel export">
+                <h2>"Export"</h2>
+                <label>"CSS"</label>
+                <div class="code">
+                    <code>{css}</code>
+                    <CopyButton value=css label=Some("Copy CSS".to_owned())/>
+                </div>
+                <label>
+                    "Baked sRGB stops, sampled "
+                    <input
+                        type="number"
+                        min="1"
+                        max="64"
+                        prop:value=baked_samples
+                        on:input=on_baked_samples_input
+                    />
+                    " times per segment"
+                </label>
+                <div class="code">
+                    <code>{baked_css}</code>
+                    <CopyButton
+                        value=baked_css
+                        label=Some("Copy baked CSS".to_owned())
+                    />
+                </div>
+            </section>
+        </div>
+    }
+}
@@ -0,0 +1,184 @@
+pub mod components;
+
+use crate::{color_picker::*, utils::*};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradientStop {
+    pub color: Rgb,
+    /// The position along the gradient, in the range 0 to 1.
+    pub position: f64,
+}
+
+/// A linear gradient, interpolated like a CSS `linear-gradient()` with a
+/// `<color-interpolation-method>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    pub stops: Vec<GradientStop>,
+    /// The angle of the gradient line in degrees, where 0 points up and 90
+    /// points to the right.
+    pub angle: f64,
+    pub space: InterpolationSpace,
+    pub hue_interpolation: HueInterpolation,
+}
+
+impl Gradient {
+    /// The stops ordered by position, with positions clamped to the range 0 to
+    /// 1.
+    pub fn sorted_stops(&self) -> Vec<GradientStop> {
+        let mut stops = self
+            .stops
+            .iter()
+            .map(|stop| GradientStop {
+                position: stop.position.clamp(0., 1.),
+                ..*stop
+            })
+            .collect::<Vec<_>>();
+        stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+        stops
+    }
+
+    /// Get the color at a position along the gradient. Returns `None` if the
+    /// gradient has no stops.
+    pub fn sample(&self, position: f64) -> Option<Rgb> {
+        let stops = self.sorted_stops();
+        let first = stops.first()?;
+        let last = stops.last()?;
+
+        if position <= first.position {
+            return Some(first.color);
+        }
+        if position >= last.position {
+            return Some(last.color);
+        }
+
+        let (start, end) = stops
+            .iter()
+            .zip(stops.iter().skip(1))
+            .find(|(_, end)| position <= end.position)?;
+
+        let length = end.position - start.position;
+        if length <= f64::EPSILON {
+            return Some(end.color);
+        }
+
+        Some(interpolate_rgb(
+            start.color,
+            end.color,
+            (position - start.position) / length,
+            self.space,
+            self.hue_interpolation,
+        ))
+    }
+
+    /// The CSS `<color-interpolation-method>` of the gradient, like
+    /// `in oklch longer hue`.
+    pub fn interpolation_method_css(&self) -> String {
+        interpolation_method_css(self.space, self.hue_interpolation)
+    }
+
+    /// Format the gradient as a CSS `linear-gradient()`, which relies on the
+    /// browser supporting interpolation color spaces.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::{color_picker::*, gradient::*};
+    ///
+    /// let gradient = Gradient {
+    ///     stops: vec![
+    ///         GradientStop {
+    ///             color: Rgb::from_hex_code("ff0000").unwrap(),
+    ///             position: 0.,
+    ///         },
+    ///         GradientStop {
+    ///             color: Rgb::from_hex_code("0000ff").unwrap(),
+    ///             position: 1.,
+    ///         },
+    ///     ],
+    ///     angle: 90.,
+    ///     space: InterpolationSpace::Oklch,
+    ///     hue_interpolation: HueInterpolation::Longer,
+    /// };
+    ///
+    /// assert_eq!(
+    ///     gradient.to_css(),
+    ///     "linear-gradient(90deg in oklch longer hue, #ff0000 0%, #0000ff 100%)"
+    ///         .to_owned()
+    /// );
+    /// ```
+    pub fn to_css(&self) -> String {
+        self.format_css(
+            &format!(
+                "{} {}",
+                self.angle_css(),
+                self.interpolation_method_css()
+            ),
+            &self.sorted_stops(),
+        )
+    }
+
+    /// Sample `samples_per_segment` colors between every pair of stops, so
+    /// that interpolating between them in sRGB looks like the gradient.
+    pub fn baked_stops(&self, samples_per_segment: usize) -> Vec<GradientStop> {
+        let stops = self.sorted_stops();
+        let Some(last) = stops.last() else {
+            return vec![];
+        };
+
+        let mut baked = stops
+            .iter()
+            .zip(stops.iter().skip(1))
+            .flat_map(|(start, end)| {
+                (0..=samples_per_segment).map(move |index| {
+                    let t = index as f64 / (samples_per_segment + 1) as f64;
+                    (start, end, t)
+                })
+            })
+            .map(|(start, end, t)| {
+                let position =
+                    start.position + (end.position - start.position) * t;
+                GradientStop {
+                    color: interpolate_rgb(
+                        start.color,
+                        end.color,
+                        t,
+                        self.space,
+                        self.hue_interpolation,
+                    ),
+                    position,
+                }
+            })
+            .collect::<Vec<_>>();
+        baked.push(*last);
+
+        baked
+    }
+
+    /// Format the gradient as a CSS `linear-gradient()` using only sRGB stops,
+    /// for targets which don't support interpolation color spaces.
+    pub fn to_baked_css(&self, samples_per_segment: usize) -> String {
+        self.format_css(
+            &self.angle_css(),
+            &self.baked_stops(samples_per_segment),
+        )
+    }
+
+    fn angle_css(&self) -> String {
+        format!("{}deg", naturally_format_float(self.angle, 0, 2))
+    }
+
+    fn format_css(&self, prelude: &str, stops: &[GradientStop]) -> String {
+        let stops = stops
+            .iter()
+            .map(|stop| {
+                format!(
+                    "#{} {}%",
+                    stop.color.as_hex_code(),
+                    naturally_format_float(stop.position * 100., 0, 2)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("linear-gradient({}, {})", prelude, stops)
+    }
+}
@@ -5,6 +5,7 @@
 pub mod app;
 pub mod color_picker;
 pub mod components;
+pub mod gradient;
 pub mod routes;
 pub mod settings;
 pub mod string_utils;
@@ -1,7 +1,13 @@
-use crate::color_picker::components::*;
-use leptos::*;
+use crate::{
+    color_picker::{components::*, *},
+    gradient::components::*,
+    settings::{cookie_set_value, cookie_value},
+    utils::create_debounced_effect,
+};
+use leptos::{logging::error, *};
 use leptos_meta::*;
 use leptos_router::*;
+use std::time::Duration;
 
 /// Renders the home page of your application.
 #[component]
@@ -16,13 +22,120 @@ pub fn RouteHome() -> impl IntoView {
     }
 }
 
+/// The cookie the last used color on the color picker page is stored in.
+const LAST_COLOR_KEY: &str = "last_color";
+/// How long the color has to stay the same before it's synced to the URL
+/// and the cookie.
+const SYNC_DELAY: Duration = Duration::from_millis(300);
+
 #[component]
 pub fn RouteColorPicker() -> impl IntoView {
+    // The query is read on the server as well, so that shared links are
+    // rendered with the right color from the start.
+    let link = use_query_map().with_untracked(|query| {
+        ColorLink::from_query_pairs(
+            query
+                .0
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+        )
+    });
+
+    // Without a link, the last used color is restored from a cookie, which is
+    // available while rendering on the server too.
+    let stored_color = cookie_value::<DynamicColor>(LAST_COLOR_KEY);
+    let link = ColorLink {
+        color_space: link
+            .color_space
+            .or(stored_color.map(|color| color.color_space())),
+        ..link
+    };
+
+    let color_space = link.color_space.unwrap_or(ColorSpace::Rgb);
+    let color = create_rw_signal(match (link.color, stored_color) {
+        (Some(rgb), _) => {
+            DynamicColor::from_color(rgb).set_color_space(color_space)
+        }
+        // Converting to the same color space could lose the hue of grays.
+        (None, Some(stored)) if stored.color_space() == color_space => stored,
+        (None, Some(stored)) => stored.set_color_space(color_space),
+        (None, None) => DynamicColor::from_floats((1., 1., 1.), color_space),
+    });
+    create_debounced_effect(color, SYNC_DELAY, |color| {
+        if cookie_set_value(LAST_COLOR_KEY, color).is_none() {
+            error!("Failed to store the last used color.");
+        }
+    });
+    let precise_inputs = create_rw_signal(false);
+    let normalised_inputs = create_rw_signal(false);
+    let library = create_rw_signal(PaletteLibrary::default());
+    let image = create_rw_signal(None);
+
+    // Keep the URL in sync, replacing the current history entry instead of
+    // adding one for every change. Browsers limit how often the entry can be
+    // replaced, so it isn't updated while the color is being dragged.
+    let query_string = create_memo(move |_| {
+        ColorLink {
+            color: Some(color().to_color::<Rgb>()),
+            color_space: Some(color().color_space()),
+            precise_inputs: Some(precise_inputs()),
+            normalised_inputs: Some(normalised_inputs()),
+        }
+        .to_query_string()
+    });
+    let location = use_location();
+    let navigate = use_navigate();
+    let navigate = store_value(navigate);
+    create_debounced_effect(query_string, SYNC_DELAY, move |query_string| {
+        if location.search.get_untracked() == query_string {
+            return;
+        }
+        navigate.with_value(|navigate| {
+            navigate(
+                &format!(
+                    "{}?{}",
+                    location.pathname.get_untracked(),
+                    query_string
+                ),
+                NavigateOptions {
+                    replace: true,
+                    scroll: false,
+                    ..Default::default()
+                },
+            )
+        });
+    });
+
     view! {
         <Title text="Color Picker | lax-utils"/>
 
         <h1>"Color Picker"</h1>
-        <ColorPicker/>
+        <ColorPicker
+            color=color
+            precise_inputs=precise_inputs
+            normalised_inputs=normalised_inputs
+            link=link
+        />
+        <Palettes color=color library=library/>
+        <ImageEyedropper color=color library=library image=image/>
+        <ImageRecolor image=image library=library/>
+        <ColorHarmonies color=color/>
+        <ColorScale color=color/>
+        <ColorMixer color=color/>
+        <IccProfileConverter color=color/>
+        // Hidden until the chromatic renotation data is vendored, since only
+        // grays can be converted without it.
+        // <MunsellNotation color=color/>
+    }
+}
+
+#[component]
+pub fn RouteGradient() -> impl IntoView {
+    view! {
+        <Title text="Gradient Builder | lax-utils"/>
+
+        <h1>"Gradient Builder"</h1>
+        <GradientBuilder/>
     }
 }
 
@@ -46,6 +46,64 @@ where
     Some(())
 }
 
+/// How long stored cookies are kept, in seconds.
+const COOKIE_MAX_AGE: u32 = 60 * 60 * 24 * 365;
+
+/// Read a value stored in a cookie. Unlike local storage, cookies are also
+/// available while rendering on the server, so values read from them are
+/// rendered the same way before and after hydrating.
+///
+/// The value must not contain any characters that are invalid in cookies,
+/// like `;`, `,` or whitespace.
+pub fn cookie_value<T>(key: &'static str) -> Option<T>
+where
+    T: FromStr + ToString,
+{
+    let cookies = match is_browser() {
+        true => document().dyn_into::<HtmlDocument>().ok()?.cookie().ok()?,
+        false => request_cookies()?,
+    };
+
+    cookies.split(';').find_map(|cookie| {
+        let (name, value) = cookie.trim().split_once('=')?;
+        if name != key {
+            return None;
+        }
+        value.parse::<T>().ok()
+    })
+}
+
+pub fn cookie_set_value<T>(key: &'static str, value: T) -> Option<()>
+where
+    T: FromStr + ToString,
+{
+    let document = document().dyn_into::<HtmlDocument>().ok()?;
+
+    let Ok(()) = document.set_cookie(&format!(
+        "{}={}; path=/; max-age={}; SameSite=Lax",
+        key,
+        value.to_string(),
+        COOKIE_MAX_AGE
+    )) else {
+        return None
+    };
+
+    Some(())
+}
+
+/// The `Cookie` header of the request currently being rendered.
+#[cfg(feature = "ssr")]
+fn request_cookies() -> Option<String> {
+    let request = use_context::<actix_web::HttpRequest>()?;
+    let header = request.headers().get(actix_web::http::header::COOKIE)?;
+    header.to_str().ok().map(str::to_owned)
+}
+
+#[cfg(not(feature = "ssr"))]
+fn request_cookies() -> Option<String> {
+    None
+}
+
 #[component]
 pub fn StoredInput<T>(
     input: HtmlElement<Input>,
@@ -53,6 +111,9 @@ pub fn StoredInput<T>(
     key: &'static str,
     #[prop(optional)] _type: PhantomData<T>,
     #[prop(optional)] value: Option<RwSignal<T>>,
+    /// Used instead of the stored value when the input is loaded.
+    #[prop(optional_no_strip)]
+    initial: Option<T>,
 ) -> impl IntoView
 where
     T: FromStr + ToString + Clone + 'static + Any,
@@ -132,8 +193,12 @@ where
     });
     store_value(listener);
 
+    let initial_value = move || {
+        initial.clone().or_else(|| local_storage_value::<T>(key))
+    };
+
     if let Some(value) = value {
-        if let Some(value_local) = local_storage_value::<T>(key) {
+        if let Some(value_local) = initial_value() {
             value.set(value_local);
         };
     };
@@ -156,7 +221,7 @@ where
     });
 
     input.on_mount(move |input| {
-        let Some(value_local) = (match local_storage_value::<T>(key) {
+        let Some(value_local) = (match initial_value() {
             Some(value) => Some(value),
             None => value.map(|value| value.get_untracked())
         }) else {
@@ -187,6 +252,9 @@ pub fn StoredRadioGroup<T, F>(
     #[prop(into)] options: MaybeSignal<Vec<(String, T)>>,
     #[prop(optional)] on_change: Option<F>,
     key: &'static str,
+    /// Used instead of the stored value when the group is loaded.
+    #[prop(optional_no_strip)]
+    initial: Option<T>,
 ) -> impl IntoView
 where
     T: Copy + Hash + 'static + Eq + ToString + FromStr,
@@ -203,7 +271,7 @@ where
     };
 
     let with_set_value = move |set_value: Box<dyn Fn(T)>| {
-        let Some(value) = local_storage_value::<T>(key) else {
+        let Some(value) = initial.or_else(|| local_storage_value::<T>(key)) else {
                     return;
         };
         // log!("set value");
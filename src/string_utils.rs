@@ -1,14 +1,211 @@
-use std::str::FromStr;
+use std::{f64::consts::TAU, str::FromStr};
 
 pub trait StringUtils {
+    /// Trim and parse a value from an input element into the desired type.
+    /// If that fails, the input is evaluated as an arithmetic expression,
+    /// with `+`, `-`, `*`, `/`, parentheses and hexadecimal numbers.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::string_utils::*;
+    ///
+    /// assert_eq!(" 42 ".parse_input::<u8>(), Some(42));
+    /// assert_eq!("128+16".parse_input::<u8>(), Some(144));
+    /// assert_eq!("255*0.4".parse_input::<f64>(), Some(102.));
+    /// assert_eq!("(200-20)/2".parse_input::<f64>(), Some(90.));
+    /// assert_eq!("-0x80 * -2".parse_input::<i32>(), Some(256));
+    /// assert_eq!("2 - -1".parse_input::<f64>(), Some(3.));
+    /// assert_eq!("1 + 2 * 3".parse_input::<f64>(), Some(7.));
+    /// assert_eq!("255*0.5".parse_input::<u8>(), None);
+    /// assert_eq!("1/0".parse_input::<f64>(), None);
+    /// assert_eq!("(1+2".parse_input::<f64>(), None);
+    /// assert_eq!("1 2".parse_input::<f64>(), None);
+    /// assert_eq!("red".parse_input::<f64>(), None);
+    /// ```
     fn parse_input<T: FromStr>(&self) -> Option<T>;
+
+    /// Parse a number from an input element, which may be written with one of
+    /// the `units`.
+    ///
+    /// # Examples
+    /// ```
+    /// use lax_utils::string_utils::*;
+    ///
+    /// let hue = NumberUnits {
+    ///     full_percent: 360.,
+    ///     full_turn: Some(360.),
+    /// };
+    /// assert_eq!("90".parse_number(hue), Some(90.));
+    /// assert_eq!("180deg".parse_number(hue), Some(180.));
+    /// assert_eq!("180°".parse_number(hue), Some(180.));
+    /// assert_eq!("0.5turn".parse_number(hue), Some(180.));
+    /// assert_eq!("50 %".parse_number(hue), Some(180.));
+    /// assert_eq!(
+    ///     "3.14rad".parse_number(hue).map(f64::round),
+    ///     Some(180.)
+    /// );
+    ///
+    /// let red = NumberUnits {
+    ///     full_percent: 255.,
+    ///     full_turn: None,
+    /// };
+    /// assert_eq!("50%".parse_number(red), Some(127.5));
+    /// assert_eq!("1turn".parse_number(red), None);
+    /// assert_eq!("red".parse_number(red), None);
+    /// ```
+    fn parse_number(&self, units: NumberUnits) -> Option<f64>;
+}
+
+/// The units a number typed into an input can be written in, besides plain
+/// numbers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberUnits {
+    /// The number `100%` stands for.
+    pub full_percent: f64,
+    /// The number a full turn stands for, if the number is an angle. Angles
+    /// can be written in `deg`, `rad` or `turn`.
+    pub full_turn: Option<f64>,
 }
 
 impl StringUtils for str {
-    /// Trim and parse a value from an input element into the desired type.
     fn parse_input<T: FromStr>(&self) -> Option<T> {
-        let result = self.trim().parse::<T>();
+        let input = self.trim();
+        if let Ok(value) = input.parse::<T>() {
+            return Some(value);
+        }
+
+        let value = Expression::new(input).evaluate()?;
+        value.to_string().parse::<T>().ok()
+    }
+
+    fn parse_number(&self, units: NumberUnits) -> Option<f64> {
+        let input = self.trim().to_lowercase();
+
+        // Scales are kept as fractions, to multiply before dividing.
+        let angle = |units_per_turn: f64| {
+            units.full_turn.map(|full_turn| (full_turn, units_per_turn))
+        };
+        let (number, scale) = if let Some(number) = input.strip_suffix('%') {
+            (number, Some((units.full_percent, 100.)))
+        } else if let Some(number) = input.strip_suffix("turn") {
+            (number, angle(1.))
+        } else if let Some(number) = input.strip_suffix("rad") {
+            (number, angle(TAU))
+        } else if let Some(number) = input.strip_suffix("deg") {
+            (number, angle(360.))
+        } else if let Some(number) = input.strip_suffix('°') {
+            (number, angle(360.))
+        } else {
+            (input.as_str(), Some((1., 1.)))
+        };
+
+        let (numerator, denominator) = scale?;
+        Some(number.parse_input::<f64>()? * numerator / denominator)
+    }
+}
+
+/// A small parser for arithmetic expressions typed into inputs.
+struct Expression<'a> {
+    input: &'a str,
+    depth: usize,
+}
+
+impl<'a> Expression<'a> {
+    /// How deep parentheses and signs can be nested, so that evaluating
+    /// doesn't overflow the stack.
+    const MAX_DEPTH: usize = 32;
+
+    fn new(input: &'a str) -> Self {
+        Self { input, depth: 0 }
+    }
+
+    fn evaluate(mut self) -> Option<f64> {
+        let value = self.sum()?;
+        self.skip_whitespace();
+
+        (self.input.is_empty() && value.is_finite()).then_some(value)
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.input = self.input.trim_start();
+    }
+
+    /// Consume the next character if it's one of `chars`.
+    fn next_of(&mut self, chars: &[char]) -> Option<char> {
+        self.skip_whitespace();
+        let next = self.input.chars().next().filter(|c| chars.contains(c))?;
+        self.input = &self.input[next.len_utf8()..];
+        Some(next)
+    }
+
+    fn sum(&mut self) -> Option<f64> {
+        let mut value = self.product()?;
+        while let Some(operator) = self.next_of(&['+', '-']) {
+            let term = self.product()?;
+            value = match operator {
+                '+' => value + term,
+                _ => value - term,
+            };
+        }
+        Some(value)
+    }
+
+    fn product(&mut self) -> Option<f64> {
+        let mut value = self.factor()?;
+        while let Some(operator) = self.next_of(&['*', '/']) {
+            let factor = self.factor()?;
+            value = match operator {
+                '*' => value * factor,
+                _ => value / factor,
+            };
+        }
+        Some(value)
+    }
+
+    fn factor(&mut self) -> Option<f64> {
+        if self.depth >= Self::MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+
+        let value = match self.next_of(&['-', '+', '(']) {
+            Some('-') => -self.factor()?,
+            Some('+') => self.factor()?,
+            Some(_) => {
+                let value = self.sum()?;
+                self.next_of(&[')'])?;
+                value
+            }
+            None => self.number()?,
+        };
+
+        self.depth -= 1;
+        Some(value)
+    }
+
+    fn number(&mut self) -> Option<f64> {
+        self.skip_whitespace();
+        let input = self.input;
+        let (digits, radix) = match input
+            .strip_prefix("0x")
+            .or_else(|| input.strip_prefix("0X"))
+        {
+            Some(hex) => (hex, 16),
+            None => (input, 10),
+        };
+
+        let length = digits
+            .find(|c: char| !(c.is_digit(radix) || radix == 10 && c == '.'))
+            .unwrap_or(digits.len());
+        let (number, rest) = digits.split_at(length);
+        self.input = rest;
 
-        result.ok()
+        if radix == 16 {
+            u64::from_str_radix(number, 16)
+                .ok()
+                .map(|number| number as f64)
+        } else {
+            number.parse().ok()
+        }
     }
 }
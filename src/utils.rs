@@ -1,12 +1,18 @@
-use crate::string_utils::*;
 use approx::*;
 use gloo_events::EventListener;
-use leptos::{html::*, leptos_dom::is_browser, *};
+use leptos::{
+    leptos_dom::{helpers::TimeoutHandle, is_browser},
+    *,
+};
 use num_traits::{AsPrimitive, Float};
 use std::{
     borrow::Cow,
+    cell::RefCell,
+    rc::Rc,
     sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
 };
+use wasm_bindgen::JsCast;
 use web_sys::Event;
 
 pub trait FloatUtils: Float {
@@ -206,34 +212,206 @@ pub fn naturally_format_float(
 /// Create an event listener on the window that is tied to the current scope,
 /// and is properly disposed of when the component is dropped.
 ///
-/// It uses a [gloo_events::EventListener] that is stored in the current scope
-/// using [store_value]cargo install trunkcargo install trunk
+/// It uses a [gloo_events::EventListener], which removes itself from the
+/// window when it's dropped in [on_cleanup].
 pub fn create_managed_window_event_listener<S, F>(event_type: S, callback: F)
 where
     S: Into<Cow<'static, str>>,
     F: FnMut(&Event) + 'static,
 {
     if is_browser() {
-        store_value(EventListener::new(&window(), event_type, callback));
+        let listener = EventListener::new(&window(), event_type, callback);
+        on_cleanup(move || drop(listener));
     }
 }
 
-/// Update a input value with a new modified version of the same value, keeping
-/// it unchanged if they are sufficiently similar (to get around floating point
-/// errors).
-#[allow(unused)]
-pub fn sync_input_value_float(
-    input_element: &HtmlElement<Input>,
-    value: f64,
-    decimals: usize,
-    force: bool,
-    format_fn: impl FnOnce(f64) -> String,
-) {
-    let current_value =
-        input_element.value().parse_input::<f64>().unwrap_or(0.);
-
-    if !value.float_compare_digits(current_value, -(decimals as i32)) || force {
-        input_element.set_value(&format_fn(value));
+/// Run `effect` with the value of `source` once it hasn't changed for `delay`,
+/// for side effects that are too expensive to run on every change, like while
+/// a color is being dragged.
+pub fn create_debounced_effect<T, S, F>(source: S, delay: Duration, effect: F)
+where
+    T: 'static,
+    S: Fn() -> T + 'static,
+    F: Fn(T) + Copy + 'static,
+{
+    let timeout = store_value::<Option<TimeoutHandle>>(None);
+    let clear = move || {
+        if let Some(handle) = timeout.get_value() {
+            handle.clear();
+        }
+    };
+
+    create_effect(move |_| {
+        let value = source();
+        clear();
+        timeout.set_value(
+            set_timeout_with_handle(move || effect(value), delay).ok(),
+        );
+    });
+    on_cleanup(clear);
+}
+
+/// Read the contents of a file selected by the user, calling `on_load` with
+/// the bytes once they're available.
+///
+/// Nothing is called if the file can't be read.
+pub fn read_file_bytes<F>(file: &web_sys::File, on_load: F)
+where
+    F: FnOnce(Vec<u8>) + 'static,
+{
+    let Ok(reader) = web_sys::FileReader::new() else {
+        return;
+    };
+
+    let reader_clone = reader.clone();
+    EventListener::once(&reader, "load", move |_| {
+        let Ok(result) = reader_clone.result() else {
+            return;
+        };
+        on_load(js_sys::Uint8Array::new(&result).to_vec());
+    })
+    .forget();
+
+    let _ = reader.read_as_array_buffer(file);
+}
+
+/// Get the 2D rendering context of a canvas.
+pub fn canvas_context_2d(
+    canvas: &web_sys::HtmlCanvasElement,
+) -> Option<web_sys::CanvasRenderingContext2d> {
+    canvas
+        .get_context("2d")
+        .ok()??
+        .dyn_into::<web_sys::CanvasRenderingContext2d>()
+        .ok()
+}
+
+/// Draw 8 bit RGBA pixels onto a canvas, resizing the canvas to fit them.
+pub fn draw_rgba_image(
+    canvas: &web_sys::HtmlCanvasElement,
+    width: u32,
+    height: u32,
+    data: &[u8],
+) -> Option<()> {
+    canvas.set_width(width);
+    canvas.set_height(height);
+
+    let data = web_sys::ImageData::new_with_u8_clamped_array_and_sh(
+        wasm_bindgen::Clamped(data),
+        width,
+        height,
+    )
+    .ok()?;
+    canvas_context_2d(canvas)?
+        .put_image_data(&data, 0., 0.)
+        .ok()
+}
+
+/// Decode an image file selected by the user, and read its pixels by drawing
+/// it to a canvas. `on_load` is given the width, height and 8 bit RGBA pixels,
+/// or `None` if the file isn't an image that the browser can decode.
+pub fn read_image_file<F>(file: &web_sys::File, on_load: F)
+where
+    F: FnOnce(Option<(u32, u32, Vec<u8>)>) + 'static,
+{
+    let Ok(url) = web_sys::Url::create_object_url_with_blob(file) else {
+        return on_load(None);
+    };
+    let Ok(image) = web_sys::HtmlImageElement::new() else {
+        let _ = web_sys::Url::revoke_object_url(&url);
+        return on_load(None);
+    };
+
+    // Only one of the `load` and `error` listeners runs, which removes both of
+    // them so that neither is leaked.
+    let listeners = Rc::new(RefCell::new(Vec::<EventListener>::new()));
+    let on_load = RefCell::new(Some(on_load));
+    let finish = {
+        let listeners = listeners.clone();
+        let url = url.clone();
+        Rc::new(move |result: Option<(u32, u32, Vec<u8>)>| {
+            let _ = web_sys::Url::revoke_object_url(&url);
+            // Listeners can't be dropped while they are running.
+            let listeners = listeners.take();
+            set_timeout(move || drop(listeners), Duration::ZERO);
+            if let Some(on_load) = on_load.take() {
+                on_load(result);
+            }
+        })
+    };
+
+    let load_listener = EventListener::once(&image, "load", {
+        let finish = finish.clone();
+        let image = image.clone();
+        move |_| finish(image_pixels(&image))
+    });
+    let error_listener =
+        EventListener::once(&image, "error", move |_| finish(None));
+    listeners
+        .borrow_mut()
+        .extend([load_listener, error_listener]);
+
+    image.set_src(&url);
+}
+
+/// Read the pixels of a loaded image by drawing it to a canvas.
+fn image_pixels(
+    image: &web_sys::HtmlImageElement,
+) -> Option<(u32, u32, Vec<u8>)> {
+    let (width, height) = (image.natural_width(), image.natural_height());
+    let canvas = document()
+        .create_element("canvas")
+        .ok()?
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .ok()?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context = canvas_context_2d(&canvas)?;
+    context
+        .draw_image_with_html_image_element(image, 0., 0.)
+        .ok()?;
+    let data = context
+        .get_image_data(0., 0., width as f64, height as f64)
+        .ok()?;
+
+    Some((width, height, data.data().0))
+}
+
+/// Let the user download `bytes` as a file called `file_name`.
+pub fn download_file(file_name: &str, mime_type: &str, bytes: &[u8]) {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let Ok(blob) = web_sys::Blob::new_with_u8_array_sequence_and_options(
+        &parts,
+        web_sys::BlobPropertyBag::new().type_(mime_type),
+    ) else {
+        return;
+    };
+    let Ok(url) = web_sys::Url::create_object_url_with_blob(&blob) else {
+        return;
+    };
+
+    download_url(file_name, &url);
+
+    // Some browsers start the download asynchronously, and cancel it if the
+    // URL is revoked before that.
+    set_timeout(
+        move || {
+            let _ = web_sys::Url::revoke_object_url(&url);
+        },
+        Duration::from_secs(10),
+    );
+}
+
+/// Let the user download the resource at `url`, like an object or data URL, as
+/// a file called `file_name`.
+pub fn download_url(file_name: &str, url: &str) {
+    let anchor = document().create_element("a").ok().and_then(|element| {
+        element.dyn_into::<web_sys::HtmlAnchorElement>().ok()
+    });
+    if let Some(anchor) = anchor {
+        anchor.set_href(url);
+        anchor.set_download(file_name);
+        anchor.click();
     }
 }
 
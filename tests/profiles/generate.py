@@ -0,0 +1,341 @@
+"""Generate the ICC profiles used by the doc tests of `icc.rs`.
+
+Both describe sRGB with D50 adapted primaries: `srgb-matrix-trc.icc` as a
+version 2 matrix/TRC profile, and `srgb-lut.icc` as a version 4 profile with
+`lutAtoB` and `lutBtoA` tags.
+
+`gray-with-rgb-tags.icc` is a malformed gray profile, which contains the tags
+of an RGB matrix/TRC profile instead of a gray tone curve.
+
+Run with `python3 tests/profiles/generate.py`.
+"""
+
+import itertools
+import os
+import struct
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+
+# The D50 white point of the profile connection space.
+WHITE_D50 = (0.9642, 1.0, 0.8249)
+
+# The sRGB primaries adapted to D50 with the Bradford transform, as the
+# columns of a matrix from linear RGB to XYZ.
+SRGB_TO_XYZ = [
+    [0.4360747, 0.3850649, 0.1430804],
+    [0.2225045, 0.7168786, 0.0606169],
+    [0.0139322, 0.0971045, 0.7141733],
+]
+
+# The parameters of the sRGB tone curve as a `para` curve of type 3:
+# gamma, a, b, c and d.
+SRGB_CURVE_PARAMETERS = [2.4, 1 / 1.055, 0.055 / 1.055, 1 / 12.92, 0.04045]
+
+# The largest value of the 16 bit XYZ encoding of `lutAtoB` and `lutBtoA`
+# tags, which is 1 + 32767/32768, relative to 65535.
+XYZ_ENCODING_SCALE = 32768 / 65535
+
+
+def s15_fixed16(value):
+    """Encode a number as a signed 15.16 fixed point number."""
+    return struct.pack(">i", round(value * 65536))
+
+
+def u16(value):
+    """Encode a number as an unsigned 16 bit integer."""
+    return struct.pack(">H", value)
+
+
+def u32(value):
+    """Encode a number as an unsigned 32 bit integer."""
+    return struct.pack(">I", value)
+
+
+def pad4(data):
+    """Pad data with zeros to a multiple of 4 bytes, as tags are aligned."""
+    return data + b"\0" * (-len(data) % 4)
+
+
+def tag_header(signature):
+    """The type signature of a tag, followed by 4 reserved bytes."""
+    return signature + b"\0" * 4
+
+
+def xyz_tag(x, y, z):
+    """An `XYZ ` tag containing a single XYZ number."""
+    data = tag_header(b"XYZ ")
+    for value in (x, y, z):
+        data += s15_fixed16(value)
+    return data
+
+
+def parametric_curve_tag(function_type, parameters):
+    """A `para` tag with the parameters of one of the curve functions."""
+    data = tag_header(b"para")
+    data += u16(function_type)
+    data += u16(0)
+    for parameter in parameters:
+        data += s15_fixed16(parameter)
+    return data
+
+
+def identity_curve_tag():
+    """A `curv` tag without any entries, which is the identity."""
+    return tag_header(b"curv") + u32(0)
+
+
+def table_curve_tag(values):
+    """A `curv` tag sampling a curve at evenly spaced points."""
+    data = tag_header(b"curv")
+    data += u32(len(values))
+    for value in values:
+        data += u16(round(value * 65535))
+    return pad4(data)
+
+
+def text_description_tag(text):
+    """A version 2 `desc` tag with an ASCII description.
+
+    The Unicode and ScriptCode descriptions are left empty.
+    """
+    ascii_text = text.encode() + b"\0"
+    data = tag_header(b"desc")
+    data += u32(len(ascii_text))
+    data += ascii_text
+    # The Unicode language code and length, and the ScriptCode code, length
+    # and 67 byte description.
+    data += b"\0" * (12 + 67)
+    return data
+
+
+def multi_localized_unicode_tag(text):
+    """A version 4 `mluc` tag with an English description."""
+    utf16_text = text.encode("utf-16-be")
+    record_count = 1
+    record_size = 12
+    # The header, the record count and size, and one record.
+    text_offset = 28
+
+    data = tag_header(b"mluc")
+    data += u32(record_count)
+    data += u32(record_size)
+    data += b"enUS"
+    data += u32(len(utf16_text))
+    data += u32(text_offset)
+    data += utf16_text
+    return data
+
+
+def identity_clut():
+    """A lookup table with 2 grid points per input channel, mapping the three
+    input channels to themselves with 16 bit precision."""
+    grid_points = [2, 2, 2]
+    precision = 2
+
+    data = bytes(grid_points + [0] * 13)
+    data += bytes([precision, 0, 0, 0])
+    for point in itertools.product(range(2), repeat=3):
+        for value in point:
+            data += u16(value * 65535)
+    return data
+
+
+def lut_a_to_b_tag(signature, matrix, offset, b_curves, m_curves, a_curves):
+    """A `mAB ` or `mBA ` tag with all of its stages.
+
+    The lookup table is the identity, so the other stages alone define the
+    transform. The elements are stored in the same order for both tag types,
+    which is allowed since the header contains their offsets.
+    """
+    header_size = 32
+    body = b""
+    offsets = {}
+
+    def add_element(name, data):
+        nonlocal body
+        offsets[name] = header_size + len(body)
+        body += pad4(data)
+
+    add_element("b_curves", b"".join(pad4(curve) for curve in b_curves))
+    matrix_data = b""
+    for row in matrix:
+        for value in row:
+            matrix_data += s15_fixed16(value)
+    for value in offset:
+        matrix_data += s15_fixed16(value)
+    add_element("matrix", matrix_data)
+    add_element("m_curves", b"".join(pad4(curve) for curve in m_curves))
+    add_element("clut", identity_clut())
+    add_element("a_curves", b"".join(pad4(curve) for curve in a_curves))
+
+    input_channels = 3
+    output_channels = 3
+    data = tag_header(signature)
+    data += bytes([input_channels, output_channels, 0, 0])
+    data += u32(offsets["b_curves"])
+    data += u32(offsets["matrix"])
+    data += u32(offsets["m_curves"])
+    data += u32(offsets["clut"])
+    data += u32(offsets["a_curves"])
+    return data + body
+
+
+def profile(device_class, color_space, pcs, tags, version=0x02100000):
+    """Build a profile from its header fields and a list of tags, given as
+    pairs of tag signatures and data."""
+    tag_table_size = 4 + 12 * len(tags)
+    data_offset = 128 + tag_table_size
+
+    tag_table = u32(len(tags))
+    tag_data = b""
+    for signature, data in tags:
+        data = pad4(data)
+        tag_table += signature
+        tag_table += u32(data_offset + len(tag_data))
+        tag_table += u32(len(data))
+        tag_data += data
+    body = tag_table + tag_data
+
+    header = u32(128 + len(body))
+    # The preferred CMM.
+    header += b"none"
+    header += u32(version)
+    header += device_class
+    header += color_space
+    header += pcs
+    # The creation date and time.
+    header += b"\0" * 12
+    header += b"acsp"
+    # The platform, flags, device manufacturer and model, and attributes.
+    header += b"\0" * 24
+    # The rendering intent.
+    header += u32(0)
+    header += s15_fixed16(WHITE_D50[0])
+    header += s15_fixed16(WHITE_D50[1])
+    header += s15_fixed16(WHITE_D50[2])
+    # The creator, ID and reserved bytes.
+    header += b"\0" * 48
+    assert len(header) == 128
+
+    return header + body
+
+
+def invert_matrix(matrix):
+    """Invert a 3x3 matrix."""
+    (a, b, c), (d, e, f), (g, h, i) = matrix
+    determinant = (
+        a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g)
+    )
+    adjugate = [
+        [e * i - f * h, c * h - b * i, b * f - c * e],
+        [f * g - d * i, a * i - c * g, c * d - a * f],
+        [d * h - e * g, b * g - a * h, a * e - b * d],
+    ]
+    return [[value / determinant for value in row] for row in adjugate]
+
+
+def scale_matrix(matrix, factor):
+    """Multiply every element of a matrix by a factor."""
+    return [[value * factor for value in row] for row in matrix]
+
+
+def srgb_encode(linear):
+    """Apply the sRGB tone curve to a linear value."""
+    if linear <= 0.0031308:
+        return 12.92 * linear
+    return 1.055 * linear ** (1 / 2.4) - 0.055
+
+
+def write_profile(file_name, data):
+    with open(os.path.join(HERE, file_name), "wb") as file:
+        file.write(data)
+
+
+def srgb_matrix_trc():
+    srgb_curve = parametric_curve_tag(3, SRGB_CURVE_PARAMETERS)
+    return profile(
+        b"mntr",
+        b"RGB ",
+        b"XYZ ",
+        [
+            # The description.
+            (b"desc", text_description_tag("Test sRGB matrix/TRC")),
+            # The media white point.
+            (b"wtpt", xyz_tag(*WHITE_D50)),
+            # The red, green and blue colorants, the columns of the matrix.
+            (b"rXYZ", xyz_tag(*[row[0] for row in SRGB_TO_XYZ])),
+            (b"gXYZ", xyz_tag(*[row[1] for row in SRGB_TO_XYZ])),
+            (b"bXYZ", xyz_tag(*[row[2] for row in SRGB_TO_XYZ])),
+            # The tone curves of the red, green and blue channels.
+            (b"rTRC", srgb_curve),
+            (b"gTRC", srgb_curve),
+            (b"bTRC", srgb_curve),
+        ],
+    )
+
+
+def srgb_lut():
+    srgb_curve = parametric_curve_tag(3, SRGB_CURVE_PARAMETERS)
+    # The inverse tone curve, sampled at every 8 bit value.
+    inverse_curve = table_curve_tag([srgb_encode(i / 255) for i in range(256)])
+
+    # Device to PCS: the A curves decode sRGB, and the matrix converts linear
+    # RGB to the encoded XYZ.
+    a_to_b = lut_a_to_b_tag(
+        b"mAB ",
+        scale_matrix(SRGB_TO_XYZ, XYZ_ENCODING_SCALE),
+        [0, 0, 0],
+        [identity_curve_tag()] * 3,
+        [identity_curve_tag()] * 3,
+        [srgb_curve] * 3,
+    )
+    # PCS to device: the matrix converts the encoded XYZ to linear RGB, and
+    # the A curves encode it as sRGB.
+    b_to_a = lut_a_to_b_tag(
+        b"mBA ",
+        scale_matrix(invert_matrix(SRGB_TO_XYZ), 1 / XYZ_ENCODING_SCALE),
+        [0, 0, 0],
+        [identity_curve_tag()] * 3,
+        [identity_curve_tag()] * 3,
+        [inverse_curve] * 3,
+    )
+
+    return profile(
+        b"mntr",
+        b"RGB ",
+        b"XYZ ",
+        [
+            # The description.
+            (b"desc", multi_localized_unicode_tag("Test sRGB LUT")),
+            # The perceptual transforms, which all intents fall back to.
+            (b"A2B0", a_to_b),
+            (b"B2A0", b_to_a),
+        ],
+        version=0x04300000,
+    )
+
+
+def gray_with_rgb_tags():
+    srgb_curve = parametric_curve_tag(3, SRGB_CURVE_PARAMETERS)
+    return profile(
+        b"mntr",
+        b"GRAY",
+        b"XYZ ",
+        [
+            # The description.
+            (b"desc", text_description_tag("Test gray with RGB tags")),
+            # The red, green and blue colorants, which only RGB profiles use.
+            (b"rXYZ", xyz_tag(*[row[0] for row in SRGB_TO_XYZ])),
+            (b"gXYZ", xyz_tag(*[row[1] for row in SRGB_TO_XYZ])),
+            (b"bXYZ", xyz_tag(*[row[2] for row in SRGB_TO_XYZ])),
+            # The tone curves of the red, green and blue channels.
+            (b"rTRC", srgb_curve),
+            (b"gTRC", srgb_curve),
+            (b"bTRC", srgb_curve),
+        ],
+    )
+
+
+write_profile("srgb-matrix-trc.icc", srgb_matrix_trc())
+write_profile("srgb-lut.icc", srgb_lut())
+write_profile("gray-with-rgb-tags.icc", gray_with_rgb_tags())